@@ -1,28 +1,81 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    attr, from_binary, to_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut,
+    Empty, Env, Event, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
+    WasmMsg,
 };
-use cw0::maybe_addr;
-use cw2::set_contract_version;
+use cw0::{maybe_addr, Duration, Expiration};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
+use cw_storage_plus::{Bound, Prefix};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GamesListResponse, InstantiateMsg, QueryMsg};
-use crate::state::{Game, GameMove, GameResult, State, ADMIN, GAME, HOOKS, STATE};
+use crate::migrations::{migrate_hooks_blacklist, migrate_legacy_state, migrate_pair_keyed_games};
+use crate::msg::{
+    ActivityResponse, AllowlistResponse, BlacklistEntryResponse, BlacklistResponse,
+    Cw20AllowlistResponse, ExecuteMsg, FeesResponse, GamesListResponse, HeadToHeadResponse,
+    InstantiateMsg, LeaderboardEntry, LeaderboardMetric, LeaderboardResponse, MigrateMsg, OrderBy,
+    PlayerStatsBatchResponse, PlayerStatsEntry, QueryMsg, ReceiveMsg,
+};
+use crate::state::{
+    denom_key, games, stake_key, Asset, BlacklistEntry, DailyActivity, Game, GameMove, GameResult,
+    GameStatus, GlobalStats, HeadToHeadGame, Match, MatchStatus, MoveStats, PauseInfo, PauseScope,
+    PlayerStats, Round, State, ADMIN, BLACKLIST, CW20_ALLOWLIST, DAILY_ACTIVITY, DAILY_PLAYERS,
+    FEES, GAME_COUNT, GLOBAL_STATS, HEAD_TO_HEAD, MATCHES, MATCH_COUNT, MOVE_STATS, PAUSE,
+    PLAYER_ALLOWLIST, PLAYER_MOVE_STATS, PLAYER_STATS, RANK_NET_WINNINGS, RANK_RATING, RANK_WINS,
+    RANK_WIN_RATE, RESPONSE_GRACE, STATE,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:rps-dapp";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::Time(24 * 60 * 60);
+const MAX_FEE_BPS: u16 = 10_000;
+const MAX_BEST_OF: u8 = 9;
+/// Length of a sha256 digest, the only commitment `hash_move` produces.
+const COMMITMENT_LEN: usize = 32;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+const MIN_WIN_RATE_GAMES: u64 = 10;
+pub(crate) const DEFAULT_RATING: u32 = 1200;
+pub(crate) const DEFAULT_K_FACTOR: u32 = 32;
+/// Games kept in the recent results of a head-to-head record.
+const HEAD_TO_HEAD_RECENT: usize = 10;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+/// Widest range of days a single `GetActivity` query may cover.
+const MAX_ACTIVITY_DAYS: u64 = 366;
+/// Expected score in basis points of the lower rated player, 1 / (1 + 10^(gap / 400)),
+/// for rating gaps of 0, 25, .. 800. Wider gaps are treated as 800.
+const ELO_EXPECTED_BPS: [i64; 33] = [
+    5000, 4641, 4285, 3937, 3599, 3275, 2966, 2675, 2403, 2150, 1917, 1704, 1510, 1334, 1177, 1035,
+    909, 797, 698, 610, 532, 464, 405, 352, 307, 267, 232, 201, 175, 152, 132, 114, 99,
+];
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let fee_bps = msg.fee_bps.unwrap_or(0);
+    if fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFee {});
+    }
+
     let state = State {
         owner: info.sender.clone(),
+        response_timeout: msg.response_timeout.unwrap_or(DEFAULT_TIMEOUT),
+        reveal_timeout: msg.reveal_timeout.unwrap_or(DEFAULT_TIMEOUT),
+        fee_bps,
+        default_rating: msg.default_rating.unwrap_or(DEFAULT_RATING),
+        k_factor: msg.k_factor.unwrap_or(DEFAULT_K_FACTOR),
+        invite_only: msg.invite_only.unwrap_or(false),
     };
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -39,33 +92,163 @@ pub fn instantiate(
         .add_attribute("admin", &info.sender))
 }
 
+/// Upgrades state written by an older release of this contract. Versions before
+/// 0.2.0 kept games keyed by player pair, the blacklist in a cw4 hooks list and a
+/// config holding only the owner.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::WrongContract {
+            name: stored.contract,
+        });
+    }
+
+    let stored_version: Version = stored.version.parse()?;
+    let current_version: Version = CONTRACT_VERSION.parse()?;
+    if stored_version > current_version {
+        return Err(ContractError::CannotDowngrade {
+            stored: stored.version,
+            current: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    let mut response = Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", &stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_legacy_state(deps.storage)?;
+        let games_migrated = migrate_pair_keyed_games(deps.storage, &env)?;
+        let blacklist_migrated = migrate_hooks_blacklist(deps.storage, &env)?;
+        response = response
+            .add_attribute("games_migrated", games_migrated.to_string())
+            .add_attribute("blacklist_migrated", blacklist_migrated.to_string());
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(response)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // only wagers are escrowed, funds sent along with anything else would be stuck
+    let takes_wager = matches!(
+        msg,
+        ExecuteMsg::StartGame { .. } | ExecuteMsg::Respond { .. } | ExecuteMsg::StartMatch { .. }
+    );
+    if !takes_wager && !info.funds.is_empty() {
+        return Err(ContractError::UnexpectedFunds {});
+    }
+
     match msg {
         ExecuteMsg::StartGame {
             opponent,
-            host_move,
-        } => try_start_game(deps, info, opponent, host_move),
-        ExecuteMsg::UpdateAdmin { admin } => try_admin_update(deps, info, admin),
-        ExecuteMsg::AddToBlacklist { address } => {
-            let valid_addr = deps.api.addr_validate(address.as_str())?;
-            Ok(HOOKS.execute_add_hook(&ADMIN, deps, info, valid_addr)?)
+            host_commitment,
+        } => {
+            let stake = native_stake(&info.funds)?;
+            try_start_game(deps, env, info.sender, opponent, host_commitment, stake)
         }
+        ExecuteMsg::UpdateAdmin { admin } => try_admin_update(deps, info, admin),
+        ExecuteMsg::AddToBlacklist {
+            address,
+            reason,
+            expires,
+        } => try_add_to_blacklist(deps, env, info, address, reason, expires),
         ExecuteMsg::RemoveFromBlacklist { address } => {
-            let valid_addr = deps.api.addr_validate(address.as_str())?;
-            Ok(HOOKS.execute_remove_hook(&ADMIN, deps, info, valid_addr)?)
+            try_remove_from_blacklist(deps, info, address)
+        }
+        ExecuteMsg::CancelBannedGames { address, limit } => {
+            try_cancel_banned_games(deps, env, info, address, limit)
         }
-        ExecuteMsg::Respond {
-            host,
+        ExecuteMsg::Respond { game_id, opp_move } => {
+            let stake = native_stake(&info.funds)?;
+            try_response(deps, env, info.sender, game_id, opp_move, stake)
+        }
+        ExecuteMsg::Reveal {
+            game_id,
+            host_move,
+            salt,
+            next_commitment,
+        } => try_reveal(deps, env, info, game_id, host_move, salt, next_commitment),
+        ExecuteMsg::StartMatch {
             opponent,
-            opp_move,
-        } => try_response(deps, info, host, opponent, opp_move),
+            best_of,
+            host_commitment,
+        } => {
+            let stake = native_stake(&info.funds)?;
+            try_start_match(
+                deps,
+                env,
+                info.sender,
+                opponent,
+                best_of,
+                host_commitment,
+                stake,
+            )
+        }
+        ExecuteMsg::ClaimTimeout { game_id } => try_claim_timeout(deps, env, info, game_id),
+        ExecuteMsg::CancelGame { game_id, reason } => {
+            try_cancel_game(deps, env, info, game_id, reason)
+        }
+        ExecuteMsg::DeclineGame { game_id, reason } => {
+            try_decline_game(deps, env, info, game_id, reason)
+        }
+        ExecuteMsg::UpdateConfig {
+            response_timeout,
+            reveal_timeout,
+            fee_bps,
+            default_rating,
+            k_factor,
+        } => try_update_config(
+            deps,
+            info,
+            response_timeout,
+            reveal_timeout,
+            fee_bps,
+            default_rating,
+            k_factor,
+        ),
+        ExecuteMsg::Pause { scope, reason } => try_pause(deps, env, info, scope, reason),
+        ExecuteMsg::Unpause {} => try_unpause(deps, env, info),
+        ExecuteMsg::SetInviteOnly { enabled } => try_set_invite_only(deps, info, enabled),
+        ExecuteMsg::AddToAllowlist { address } => try_add_to_allowlist(deps, info, address),
+        ExecuteMsg::RemoveFromAllowlist { address } => {
+            try_remove_from_allowlist(deps, info, address)
+        }
+        ExecuteMsg::Receive(msg) => try_receive(deps, env, info, msg),
+        ExecuteMsg::AllowCw20 { contract } => try_allow_cw20(deps, info, contract),
+        ExecuteMsg::DisallowCw20 { contract } => try_disallow_cw20(deps, info, contract),
+        ExecuteMsg::WithdrawFees {
+            denom,
+            amount,
+            recipient,
+        } => try_withdraw_fees(deps, info, denom, amount, recipient),
+    }
+}
+
+/// Commitment the host submits with `StartGame`: sha256(move || salt || host).
+pub fn hash_move(host: &Addr, host_move: GameMove, salt: &str) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(host_move.as_str().as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.update(host.as_bytes());
+    Binary::from(hasher.finalize().as_slice())
+}
+
+/// Rejects a commitment that could never be revealed because it is no sha256 digest.
+fn assert_commitment(commitment: &Binary) -> Result<(), ContractError> {
+    if commitment.len() != COMMITMENT_LEN {
+        return Err(ContractError::InvalidCommitment {});
     }
+    Ok(())
 }
 
 pub fn try_admin_update(
@@ -75,564 +258,5141 @@ pub fn try_admin_update(
 ) -> Result<Response, ContractError> {
     let val_addr = maybe_addr(deps.api, Some(admin.to_string()))?;
 
-    return Ok(ADMIN.execute_update_admin(deps, info, val_addr)?);
+    Ok(ADMIN.execute_update_admin(deps, info, val_addr)?)
 }
 
-pub fn try_start_game(
+pub fn try_update_config(
     deps: DepsMut,
     info: MessageInfo,
-    opponent: Addr,
-    host_move: GameMove,
+    response_timeout: Option<Duration>,
+    reveal_timeout: Option<Duration>,
+    fee_bps: Option<u16>,
+    default_rating: Option<u32>,
+    k_factor: Option<u32>,
 ) -> Result<Response, ContractError> {
-    let hooks = HOOKS.query_hooks(deps.as_ref())?.hooks;
-
-    for blacklisted_address in hooks.iter() {
-        if blacklisted_address == &info.sender {
-            return Err(ContractError::HostAddressBlacklisted {});
-        }
-    }
-
-    let val_addr = deps.api.addr_validate(opponent.as_str())?;
-
-    let game = GAME.may_load(deps.storage, (&info.sender, &val_addr))?;
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    match game {
-        Some(_) => return Err(ContractError::OnlyOneGameAtATime {}),
-        None => {
-            let game_info = Game {
-                host: info.sender.clone(),
-                opponent: val_addr.clone(),
-                host_move: host_move,
-                opp_move: None,
-                result: None,
-            };
+    let mut state = STATE.load(deps.storage)?;
 
-            GAME.save(deps.storage, (&info.sender, &val_addr), &game_info)?;
+    if let Some(response_timeout) = response_timeout {
+        state.response_timeout = response_timeout;
+    }
+    if let Some(reveal_timeout) = reveal_timeout {
+        state.reveal_timeout = reveal_timeout;
+    }
+    if let Some(fee_bps) = fee_bps {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidFee {});
         }
+        state.fee_bps = fee_bps;
+    }
+    if let Some(default_rating) = default_rating {
+        state.default_rating = default_rating;
     }
+    if let Some(k_factor) = k_factor {
+        state.k_factor = k_factor;
+    }
+
+    STATE.save(deps.storage, &state)?;
 
-    Ok(Response::new().add_attribute("method", "try_start_game"))
+    Ok(Response::new().add_attribute("method", "update_config"))
 }
 
-fn try_response(
+pub fn try_pause(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    host: Addr,
-    opponent: Addr,
-    opp_move: GameMove,
+    scope: PauseScope,
+    reason: Option<String>,
 ) -> Result<Response, ContractError> {
-    if info.sender != opponent {
-        return Err(ContractError::Unauthorized {});
-    }
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    let mut game_found = query_game(deps.as_ref(), host.clone(), opponent.clone())?;
+    if scope != PauseScope::All {
+        start_response_grace(deps.storage, &env)?;
+    }
 
-    game_found.opp_move = Some(opp_move);
+    let pause = PauseInfo {
+        scope,
+        reason: reason.clone(),
+        paused_by: info.sender,
+        paused_at: env.block.time,
+    };
+    PAUSE.save(deps.storage, &pause)?;
 
-    if game_found.host_move == GameMove::Rock && opp_move == GameMove::Scissors
-        || game_found.host_move == GameMove::Paper && opp_move == GameMove::Rock
-        || game_found.host_move == GameMove::Scissors && opp_move == GameMove::Paper
-    {
-        game_found.result = Some(GameResult::HostWins);
-    } else if game_found.host_move == opp_move {
-        game_found.result = Some(GameResult::Tie);
-    } else {
-        game_found.result = Some(GameResult::OpponentWins);
-    };
-
-    let game_result = game_found.result.clone();
-
-    let update_game = |game: Option<Game>| -> Result<Game, ContractError> {
-        match game {
-            Some(_) => {
-                let game = Game {
-                    host: game_found.host,
-                    opponent: game_found.opponent,
-                    host_move: game_found.host_move,
-                    opp_move: game_found.opp_move,
-                    result: game_found.result.clone(),
-                };
-                return Ok(game);
-            }
-            None => {
-                return Err(ContractError::NoGameFound {});
-            }
-        }
+    let scope_string = match scope {
+        PauseScope::NewGames => "new_games",
+        PauseScope::All => "all",
     };
 
-    GAME.update(deps.storage, (&host, &opponent), update_game)?;
+    Ok(Response::new()
+        .add_attribute("method", "pause")
+        .add_attribute("scope", scope_string)
+        .add_attribute("reason", reason.unwrap_or_else(|| "none".to_string())))
+}
 
-    GAME.remove(deps.storage, (&host.clone(), &opponent.clone()));
+pub fn try_unpause(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    let result_string = match game_result {
-        Some(GameResult::HostWins) => "Host Won",
-        Some(GameResult::OpponentWins) => "Opponent Won",
-        Some(GameResult::Tie) => "Tie",
-        None => "None",
-    };
+    start_response_grace(deps.storage, &env)?;
+    PAUSE.remove(deps.storage);
 
-    Ok(Response::new()
-        .add_attribute("method", "response")
-        .add_attribute("result", result_string))
+    Ok(Response::new().add_attribute("method", "unpause"))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetOwner {} => to_binary(&query_owner(deps)?),
-        QueryMsg::GetGamesByHost { address } => to_binary(&query_game_by_host(deps, address)?),
-        QueryMsg::GetGamesByOpponent { opponent } => to_binary(&query_game_by_opp(deps, opponent)?),
-        QueryMsg::GetGame { host, opponent } => to_binary(&query_game(deps, host, opponent)?),
-        QueryMsg::GetAdmin {} => to_binary(&query_admin(deps)?),
+/// Opens a fresh response window when a pause that blocked responses is lifted.
+fn start_response_grace(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    if let Some(PauseInfo {
+        scope: PauseScope::All,
+        ..
+    }) = PAUSE.may_load(storage)?
+    {
+        let state = STATE.load(storage)?;
+        RESPONSE_GRACE.save(storage, &state.response_timeout.after(&env.block))?;
     }
+    Ok(())
 }
 
-fn query_owner(deps: Deps) -> StdResult<Addr> {
-    let state = STATE.load(deps.storage)?;
-    Ok(Addr::from(state.owner))
+pub fn try_set_invite_only(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        state.invite_only = enabled;
+        Ok(state)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_invite_only")
+        .add_attribute("enabled", enabled.to_string()))
 }
 
-fn query_game(deps: Deps, host: Addr, opponent: Addr) -> StdResult<Game> {
-    let val_host_addr = deps.api.addr_validate(&host.as_str())?;
-    let val_opp_addr = deps.api.addr_validate(&opponent.as_str())?;
+pub fn try_add_to_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    let game = GAME.may_load(deps.storage, (&val_host_addr, &val_opp_addr))?;
+    let val_addr = deps.api.addr_validate(address.as_str())?;
+    PLAYER_ALLOWLIST.save(deps.storage, &val_addr, &Empty {})?;
 
-    match game {
-        Some(g) => Ok(Game {
-            host: g.host,
-            opponent: g.opponent,
-            host_move: g.host_move,
-            opp_move: g.opp_move,
-            result: g.result,
-        }),
-        None => Err(StdError::generic_err("Game not found")),
-    }
+    Ok(Response::new()
+        .add_attribute("method", "add_to_allowlist")
+        .add_attribute("address", val_addr))
 }
 
-fn query_game_by_host(deps: Deps, address: Addr) -> StdResult<GamesListResponse> {
-    let validated_addr = deps.api.addr_validate(&address.as_str())?;
+pub fn try_remove_from_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    let mut games_found: Vec<Game> = vec![];
+    let val_addr = deps.api.addr_validate(address.as_str())?;
+    PLAYER_ALLOWLIST.remove(deps.storage, &val_addr);
 
-    let games_queried: StdResult<Vec<_>> = GAME
-        .prefix(&validated_addr)
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect();
+    Ok(Response::new()
+        .add_attribute("method", "remove_from_allowlist")
+        .add_attribute("address", val_addr))
+}
 
-    for games_queried in &games_queried? {
-        games_found.push(games_queried.1.clone());
-    }
+pub fn try_allow_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: Addr,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    Ok(GamesListResponse { games: games_found })
+    let val_addr = deps.api.addr_validate(contract.as_str())?;
+    CW20_ALLOWLIST.save(deps.storage, &val_addr, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("method", "allow_cw20")
+        .add_attribute("contract", val_addr))
 }
 
-fn query_game_by_opp(deps: Deps, opponent: Addr) -> StdResult<GamesListResponse> {
-    let validated_addr = deps.api.addr_validate(&opponent.as_str())?;
+pub fn try_disallow_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: Addr,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let val_addr = deps.api.addr_validate(contract.as_str())?;
+    CW20_ALLOWLIST.remove(deps.storage, &val_addr);
 
-    let mut games_found: Vec<Game> = vec![];
+    Ok(Response::new()
+        .add_attribute("method", "disallow_cw20")
+        .add_attribute("contract", val_addr))
+}
 
-    let games_queried: StdResult<Vec<_>> = GAME
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect();
+pub fn try_add_to_blacklist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+    reason: Option<String>,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    for games_queried in &games_queried? {
-        if validated_addr == games_queried.1.opponent {
-            games_found.push(games_queried.1.clone());
+    let val_addr = deps.api.addr_validate(address.as_str())?;
+    if let Some(expires) = &expires {
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::InvalidExpiration {});
         }
     }
 
-    Ok(GamesListResponse { games: games_found })
-}
+    let entry = BlacklistEntry {
+        reason,
+        added_by: info.sender,
+        added_at: env.block.time,
+        expires,
+    };
+    BLACKLIST.save(deps.storage, &val_addr, &entry)?;
 
-fn query_admin(deps: Deps) -> StdResult<Option<Addr>> {
-    Ok(ADMIN.get(deps)?)
+    Ok(Response::new()
+        .add_attribute("method", "add_to_blacklist")
+        .add_attribute("address", val_addr))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
-
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
-
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+pub fn try_cancel_banned_games(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-        // query owner
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
-        let value: Addr = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("creator"), value);
+    let val_addr = deps.api.addr_validate(address.as_str())?;
+    if !is_blacklisted(deps.as_ref(), &env, &val_addr)? {
+        return Err(ContractError::NotBlacklisted {});
     }
 
-    #[test]
-    fn query_games_by_host() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+    let (cancelled, payouts, remaining) =
+        cancel_active_games(deps.storage, &env, &val_addr, page_limit(limit))?;
+    let events = cancelled.iter().map(|game_id| {
+        Event::new("game_cancelled")
+            .add_attribute("game_id", game_id.to_string())
+            .add_attribute("reason", "player_blacklisted")
+    });
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    Ok(Response::new()
+        .add_messages(payouts)
+        .add_events(events)
+        .add_attribute("method", "cancel_banned_games")
+        .add_attribute("address", val_addr)
+        .add_attribute("cancelled_games", cancelled.len().to_string())
+        .add_attribute("games_remaining", remaining.to_string()))
+}
 
-        // execute start game w/ 1st opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("first_player"),
-            host_move: GameMove::Rock,
-        };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+pub fn try_remove_from_blacklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-        // execute start game w/ 2nd opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("second_player"),
-            host_move: GameMove::Paper,
-        };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let val_addr = deps.api.addr_validate(address.as_str())?;
+    if !BLACKLIST.has(deps.storage, &val_addr) {
+        return Err(ContractError::NotBlacklisted {});
+    }
+    BLACKLIST.remove(deps.storage, &val_addr);
 
-        // execute start game w/ 3rd opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("third_player"),
-            host_move: GameMove::Scissors,
-        };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    Ok(Response::new()
+        .add_attribute("method", "remove_from_blacklist")
+        .add_attribute("address", val_addr))
+}
 
-        // query games by host address
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::GetGamesByHost {
-                address: Addr::unchecked("creator"),
-            },
-        )
-        .unwrap();
+pub fn try_withdraw_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: Denom,
+    amount: Option<Uint128>,
+    recipient: Option<Addr>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-        let value: GamesListResponse = from_binary(&res).unwrap();
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(recipient.as_str())?,
+        None => info.sender,
+    };
 
-        assert_eq!(3, value.games.len());
+    let key = denom_key(&denom);
+    let mut accrued = FEES
+        .may_load(deps.storage, &key)?
+        .ok_or(ContractError::InsufficientFees {})?;
 
-        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
-        assert_eq!(Addr::unchecked("first_player"), value.games[0].opponent);
-        assert_eq!(GameMove::Rock, value.games[0].host_move);
-        assert_eq!(None, value.games[0].opp_move);
-        assert_eq!(None, value.games[0].result);
+    let amount = amount.unwrap_or(accrued.amount);
+    if amount.is_zero() || amount > accrued.amount {
+        return Err(ContractError::InsufficientFees {});
+    }
 
-        assert_eq!(Addr::unchecked("creator"), value.games[1].host);
-        assert_eq!(Addr::unchecked("second_player"), value.games[1].opponent);
-        assert_eq!(GameMove::Paper, value.games[1].host_move);
-        assert_eq!(None, value.games[1].opp_move);
-        assert_eq!(None, value.games[1].result);
+    accrued.amount -= amount;
+    if accrued.amount.is_zero() {
+        FEES.remove(deps.storage, &key);
+    } else {
+        FEES.save(deps.storage, &key, &accrued)?;
     }
 
-    #[test]
-    fn query_games_by_opp() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+    Ok(Response::new()
+        .add_message(transfer(&accrued.denom, amount, &recipient)?)
+        .add_attribute("method", "withdraw_fees")
+        .add_attribute("amount", amount)
+        .add_attribute("recipient", recipient))
+}
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+pub fn try_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // info.sender is the cw20 contract that forwarded the tokens
+    if !CW20_ALLOWLIST.has(deps.storage, &info.sender) {
+        return Err(ContractError::Cw20NotAllowed {});
+    }
+    if wrapper.amount.is_zero() {
+        return Err(ContractError::InvalidStake {});
+    }
 
-        // execute start game w/ opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
-        };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let stake = Some(Asset {
+        denom: Denom::Cw20(info.sender),
+        amount: wrapper.amount,
+    });
 
-        // query games by opponent address
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::GetGamesByOpponent {
-                opponent: Addr::unchecked("other_player"),
-            },
-        )
-        .unwrap();
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::StartGame {
+            opponent,
+            host_commitment,
+        } => try_start_game(deps, env, sender, opponent, host_commitment, stake),
+        ReceiveMsg::Respond { game_id, opp_move } => {
+            try_response(deps, env, sender, game_id, opp_move, stake)
+        }
+        ReceiveMsg::StartMatch {
+            opponent,
+            best_of,
+            host_commitment,
+        } => try_start_match(deps, env, sender, opponent, best_of, host_commitment, stake),
+    }
+}
 
-        let value: GamesListResponse = from_binary(&res).unwrap();
+pub fn try_start_game(
+    deps: DepsMut,
+    env: Env,
+    host: Addr,
+    opponent: Option<Addr>,
+    host_commitment: Binary,
+    stake: Option<Asset>,
+) -> Result<Response, ContractError> {
+    if is_blacklisted(deps.as_ref(), &env, &host)? {
+        return Err(ContractError::HostAddressBlacklisted {});
+    }
+    assert_invited(deps.storage, &host)?;
+    assert_not_paused(deps.storage, PauseScope::NewGames)?;
+    assert_commitment(&host_commitment)?;
 
-        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
-        assert_eq!(Addr::unchecked("other_player"), value.games[0].opponent);
-        assert_eq!(GameMove::Rock, value.games[0].host_move);
-        assert_eq!(None, value.games[0].opp_move);
-        assert_eq!(None, value.games[0].result);
+    let val_addr = match opponent {
+        Some(opponent) => Some(deps.api.addr_validate(opponent.as_str())?),
+        None => None,
+    };
+    if val_addr.as_ref() == Some(&host) {
+        return Err(ContractError::CannotPlaySelf {});
+    }
+    if let Some(opponent) = &val_addr {
+        if is_blacklisted(deps.as_ref(), &env, opponent)? {
+            return Err(ContractError::OpponentAddressBlacklisted {});
+        }
+        if !is_invited(deps.storage, opponent)? {
+            return Err(ContractError::OpponentNotAllowlisted {});
+        }
     }
 
-    #[test]
-    fn query_game_by_opp_and_host() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+    let game_id = new_game(
+        deps.storage,
+        &env,
+        host,
+        val_addr,
+        host_commitment,
+        stake.clone(),
+        None,
+    )?;
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let stake_string = stake.map_or_else(|| "none".to_string(), |c| c.to_string());
 
-        // execute start game w/ opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+    Ok(Response::new()
+        .set_data(to_binary(&game_id)?)
+        .add_attribute("method", "try_start_game")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("stake", stake_string))
+}
+
+pub fn try_start_match(
+    deps: DepsMut,
+    env: Env,
+    host: Addr,
+    opponent: Addr,
+    best_of: u8,
+    host_commitment: Binary,
+    stake: Option<Asset>,
+) -> Result<Response, ContractError> {
+    if is_blacklisted(deps.as_ref(), &env, &host)? {
+        return Err(ContractError::HostAddressBlacklisted {});
+    }
+    assert_invited(deps.storage, &host)?;
+    assert_not_paused(deps.storage, PauseScope::NewGames)?;
+    assert_commitment(&host_commitment)?;
+
+    if !(3..=MAX_BEST_OF).contains(&best_of) || best_of % 2 != 1 {
+        return Err(ContractError::InvalidBestOf {});
+    }
+
+    let val_addr = deps.api.addr_validate(opponent.as_str())?;
+    if val_addr == host {
+        return Err(ContractError::CannotPlaySelf {});
+    }
+    if is_blacklisted(deps.as_ref(), &env, &val_addr)? {
+        return Err(ContractError::OpponentAddressBlacklisted {});
+    }
+    if !is_invited(deps.storage, &val_addr)? {
+        return Err(ContractError::OpponentNotAllowlisted {});
+    }
+
+    let match_id = MATCH_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+
+    // rounds carry no stake of their own, the match escrows it
+    let game_id = new_game(
+        deps.storage,
+        &env,
+        host.clone(),
+        Some(val_addr.clone()),
+        host_commitment,
+        None,
+        Some(match_id),
+    )?;
+
+    let game_match = Match {
+        id: match_id,
+        host,
+        opponent: val_addr,
+        best_of,
+        host_score: 0,
+        opp_score: 0,
+        rounds: vec![],
+        current_game: Some(game_id),
+        stake: stake.clone(),
+        status: MatchStatus::InProgress,
+        result: None,
+    };
+
+    MATCHES.save(deps.storage, match_id, &game_match)?;
+    MATCH_COUNT.save(deps.storage, &match_id)?;
+
+    let stake_string = stake.map_or_else(|| "none".to_string(), |c| c.to_string());
+
+    Ok(Response::new()
+        .set_data(to_binary(&match_id)?)
+        .add_attribute("method", "start_match")
+        .add_attribute("match_id", match_id.to_string())
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("best_of", best_of.to_string())
+        .add_attribute("stake", stake_string))
+}
+
+/// Saves a new game awaiting a response and returns its id.
+fn new_game(
+    storage: &mut dyn Storage,
+    env: &Env,
+    host: Addr,
+    opponent: Option<Addr>,
+    host_commitment: Binary,
+    stake: Option<Asset>,
+    match_id: Option<u64>,
+) -> StdResult<u64> {
+    let state = STATE.load(storage)?;
+    let game_id = GAME_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+
+    let game_info = Game {
+        id: game_id,
+        host,
+        opponent,
+        host_commitment,
+        host_move: None,
+        opp_move: None,
+        status: GameStatus::AwaitingResponse,
+        result: None,
+        stake,
+        created_height: env.block.height,
+        created_time: env.block.time,
+        expires: state.response_timeout.after(&env.block),
+        match_id,
+        finished_height: None,
+        finished_time: None,
+    };
+
+    games().save(storage, game_id, &game_info)?;
+    GAME_COUNT.save(storage, &game_id)?;
+    update_global_stats(storage, |stats| {
+        stats.games_started += 1;
+        Ok(())
+    })?;
+
+    Ok(game_id)
+}
+
+fn try_response(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    game_id: u64,
+    opp_move: GameMove,
+    stake: Option<Asset>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, PauseScope::All)?;
+
+    let state = STATE.load(deps.storage)?;
+
+    let mut game = games()
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    match &game.opponent {
+        Some(opponent) if opponent != &sender => return Err(ContractError::Unauthorized {}),
+        Some(_) => {
+            if is_blacklisted(deps.as_ref(), &env, &sender)? {
+                return Err(ContractError::ResponderAddressBlacklisted {});
+            }
+        }
+        None => {
+            // first eligible address to respond accepts the open challenge
+            if sender == game.host {
+                return Err(ContractError::CannotPlaySelf {});
+            }
+            if is_blacklisted(deps.as_ref(), &env, &sender)? {
+                return Err(ContractError::AcceptorAddressBlacklisted {});
+            }
+            game.opponent = Some(sender.clone());
+        }
+    }
+
+    if !game.status.is_active() {
+        return Err(ContractError::GameEnded {});
+    }
+
+    if game.status != GameStatus::AwaitingResponse {
+        return Err(ContractError::AlreadyResponded {});
+    }
+
+    assert_invited(deps.storage, &sender)?;
+
+    // the opponent escrows a match stake when responding to its first round
+    let expected_stake = match game.match_id {
+        Some(match_id) => {
+            let game_match = MATCHES.load(deps.storage, match_id)?;
+            if game_match.rounds.is_empty() {
+                game_match.stake
+            } else {
+                None
+            }
+        }
+        None => game.stake.clone(),
+    };
+    assert_stake_matches(&expected_stake, &stake)?;
+
+    game.opp_move = Some(opp_move);
+    game.status = GameStatus::AwaitingReveal;
+    game.expires = state.reveal_timeout.after(&env.block);
+
+    games().save(deps.storage, game_id, &game)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "response")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", game.host)
+        .add_attribute("opponent", sender))
+}
+
+fn try_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    host_move: GameMove,
+    salt: String,
+    next_commitment: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let mut game_found = games()
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if info.sender != game_found.host {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let opp_move = match (&game_found.status, game_found.opp_move) {
+        (GameStatus::AwaitingReveal, Some(opp_move)) => opp_move,
+        (status, _) if !status.is_active() => return Err(ContractError::GameEnded {}),
+        _ => return Err(ContractError::AwaitingResponse {}),
+    };
+
+    if hash_move(&game_found.host, host_move, &salt) != game_found.host_commitment {
+        return Err(ContractError::InvalidReveal {});
+    }
+
+    game_found.host_move = Some(host_move);
+    game_found.result = Some(game_result(host_move, opp_move));
+
+    let rating_changes = finish_game(deps.storage, &env, &mut game_found)?;
+
+    let payouts = settle_stakes(deps.storage, &env, &game_found)?;
+
+    let result_string = match game_found.result {
+        Some(GameResult::HostWins) => "Host Won",
+        Some(GameResult::OpponentWins) => "Opponent Won",
+        Some(GameResult::Tie) => "Tie",
+        None => "None",
+    };
+
+    let response = Response::new()
+        .add_messages(payouts)
+        .add_attribute("method", "reveal")
+        .add_attribute("result", result_string)
+        .add_attribute("game_id", game_id.to_string())
+        .add_attributes(rating_changes);
+
+    match game_found.match_id {
+        Some(match_id) => {
+            record_round(deps, &env, match_id, &game_found, next_commitment, response)
+        }
+        None => Ok(response),
+    }
+}
+
+/// Adds a revealed game to its match. Once a player holds the majority the match
+/// stake is settled, otherwise the next round is opened with `next_commitment`.
+fn record_round(
+    deps: DepsMut,
+    env: &Env,
+    match_id: u64,
+    game: &Game,
+    next_commitment: Option<Binary>,
+    response: Response,
+) -> Result<Response, ContractError> {
+    let mut game_match = MATCHES.load(deps.storage, match_id)?;
+
+    let (host_move, opp_move, result) = match (game.host_move, game.opp_move, &game.result) {
+        (Some(host_move), Some(opp_move), Some(result)) => (host_move, opp_move, result.clone()),
+        _ => return Err(StdError::generic_err("Round recorded before it was resolved").into()),
+    };
+
+    match result {
+        GameResult::HostWins => game_match.host_score += 1,
+        GameResult::OpponentWins => game_match.opp_score += 1,
+        GameResult::Tie => {}
+    }
+    game_match.rounds.push(Round {
+        game_id: game.id,
+        host_move,
+        opp_move,
+        result,
+    });
+
+    let mut response = response
+        .add_attribute("match_id", match_id.to_string())
+        .add_attribute("host_score", game_match.host_score.to_string())
+        .add_attribute("opp_score", game_match.opp_score.to_string());
+
+    if game_match.host_score >= game_match.wins_needed() {
+        response = response.add_messages(finish_match(
+            deps.storage,
+            env,
+            &mut game_match,
+            Some(GameResult::HostWins),
+        )?);
+    } else if game_match.opp_score >= game_match.wins_needed() {
+        response = response.add_messages(finish_match(
+            deps.storage,
+            env,
+            &mut game_match,
+            Some(GameResult::OpponentWins),
+        )?);
+    } else {
+        let next_commitment = next_commitment.ok_or(ContractError::MissingNextCommitment {})?;
+        assert_commitment(&next_commitment)?;
+        let next_game_id = new_game(
+            deps.storage,
+            env,
+            game_match.host.clone(),
+            Some(game_match.opponent.clone()),
+            next_commitment,
+            None,
+            Some(match_id),
+        )?;
+        game_match.current_game = Some(next_game_id);
+        response = response.add_attribute("next_game_id", next_game_id.to_string());
+    }
+
+    MATCHES.save(deps.storage, match_id, &game_match)?;
+
+    Ok(response)
+}
+
+/// Closes a match and releases its stake. Without a result the match never got
+/// under way, so it is kept as cancelled and the host refunded.
+fn finish_match(
+    storage: &mut dyn Storage,
+    env: &Env,
+    game_match: &mut Match,
+    result: Option<GameResult>,
+) -> StdResult<Vec<CosmosMsg>> {
+    game_match.current_game = None;
+    game_match.result = result;
+
+    let payouts = settle(
+        storage,
+        env,
+        &game_match.host,
+        Some(&game_match.opponent),
+        game_match.stake.as_ref(),
+        game_match.result.as_ref(),
+    )?;
+
+    match game_match.result {
+        Some(_) => game_match.status = MatchStatus::Finished,
+        None => {
+            game_match.status = MatchStatus::Cancelled;
+            MATCHES.save(storage, game_match.id, game_match)?;
+        }
+    }
+
+    Ok(payouts)
+}
+
+fn try_cancel_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut game_found = load_pending_game(deps.as_ref(), game_id)?;
+
+    if info.sender != game_found.host {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    close_game(deps.storage, &env, &mut game_found, GameStatus::Cancelled)?;
+    let payouts = withdraw_game(deps.storage, &env, &game_found)?;
+
+    let event = Event::new("game_cancelled")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", &game_found.host)
+        .add_attribute(
+            "reason",
+            reason.unwrap_or_else(|| "cancelled_by_host".to_string()),
+        );
+
+    Ok(Response::new()
+        .add_messages(payouts)
+        .add_event(event)
+        .add_attribute("method", "cancel_game")
+        .add_attribute("game_id", game_id.to_string()))
+}
+
+fn try_decline_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut game_found = load_pending_game(deps.as_ref(), game_id)?;
+
+    if Some(&info.sender) != game_found.opponent.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    close_game(deps.storage, &env, &mut game_found, GameStatus::Cancelled)?;
+    let payouts = withdraw_game(deps.storage, &env, &game_found)?;
+
+    let event = Event::new("game_declined")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("opponent", &info.sender)
+        .add_attribute(
+            "reason",
+            reason.unwrap_or_else(|| "declined_by_opponent".to_string()),
+        );
+
+    Ok(Response::new()
+        .add_messages(payouts)
+        .add_event(event)
+        .add_attribute("method", "decline_game")
+        .add_attribute("game_id", game_id.to_string()))
+}
+
+/// Loads a game that can still be withdrawn, i.e. nobody has responded to it yet.
+fn load_pending_game(deps: Deps, game_id: u64) -> Result<Game, ContractError> {
+    let game_found = games()
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if !game_found.status.is_active() {
+        return Err(ContractError::GameEnded {});
+    }
+
+    if game_found.status != GameStatus::AwaitingResponse {
+        return Err(ContractError::AlreadyResponded {});
+    }
+
+    if let Some(match_id) = game_found.match_id {
+        let game_match = MATCHES.load(deps.storage, match_id)?;
+        if !game_match.rounds.is_empty() {
+            return Err(ContractError::MatchInProgress {});
+        }
+    }
+
+    Ok(game_found)
+}
+
+/// Refunds the host of a withdrawn game, or of its match when it is the first round.
+fn withdraw_game(storage: &mut dyn Storage, env: &Env, game: &Game) -> StdResult<Vec<CosmosMsg>> {
+    match game.match_id {
+        Some(match_id) => {
+            let mut game_match = MATCHES.load(storage, match_id)?;
+            finish_match(storage, env, &mut game_match, None)
+        }
+        None => settle_stakes(storage, env, game),
+    }
+}
+
+/// Cancels the `limit` oldest games `address` is still playing through
+/// `cancel_and_refund`. Returns the ids of the cancelled games, the refunds and
+/// whether any active games are left.
+fn cancel_active_games(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+    limit: usize,
+) -> StdResult<(Vec<u64>, Vec<CosmosMsg>, bool)> {
+    // only the active status prefixes are read, the player's history is skipped.
+    // One game past the limit is read from each to tell whether any are left.
+    let mut active = vec![];
+    for status in GameStatus::ACTIVE {
+        let status = status.as_str().to_string();
+        let hosted: StdResult<Vec<_>> = games()
+            .idx
+            .host_status
+            .prefix((address.clone(), status.clone()))
+            .range(storage, None, None, Order::Ascending)
+            .take(limit + 1)
+            .collect();
+        let joined: StdResult<Vec<_>> = games()
+            .idx
+            .opponent_status
+            .prefix((address.to_string(), status))
+            .range(storage, None, None, Order::Ascending)
+            .take(limit + 1)
+            .collect();
+        active.extend(hosted?);
+        active.extend(joined?);
+    }
+    active.sort_by_key(|(id, _)| *id);
+    let remaining = active.len() > limit;
+    active.truncate(limit);
+
+    let mut cancelled = vec![];
+    let mut payouts = vec![];
+    for (game_id, mut game) in active {
+        payouts.extend(cancel_and_refund(storage, env, &mut game)?);
+        cancelled.push(game_id);
+    }
+
+    Ok((cancelled, payouts, remaining))
+}
+
+/// Cancels an active game and refunds what was escrowed for it. Once the opponent
+/// has paid in, both players get their stake back without a fee; a match that is
+/// already under way is kept as cancelled.
+fn cancel_and_refund(
+    storage: &mut dyn Storage,
+    env: &Env,
+    game: &mut Game,
+) -> StdResult<Vec<CosmosMsg>> {
+    let responded = game.status == GameStatus::AwaitingReveal;
+    close_game(storage, env, game, GameStatus::Cancelled)?;
+
+    match game.match_id {
+        Some(match_id) => {
+            let mut game_match = MATCHES.load(storage, match_id)?;
+            if game_match.rounds.is_empty() && !responded {
+                return finish_match(storage, env, &mut game_match, None);
+            }
+            game_match.current_game = None;
+            game_match.status = MatchStatus::Cancelled;
+            MATCHES.save(storage, match_id, &game_match)?;
+            refund_stakes(
+                &game_match.host,
+                Some(&game_match.opponent),
+                game_match.stake.as_ref(),
+            )
+        }
+        None if responded => refund_stakes(&game.host, game.opponent.as_ref(), game.stake.as_ref()),
+        None => settle_stakes(storage, env, game),
+    }
+}
+
+fn try_claim_timeout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let mut game_found = games()
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::NoGameFound {})?;
+
+    if !game_found.status.is_active() {
+        return Err(ContractError::GameEnded {});
+    }
+
+    if !game_found.expires.is_expired(&env.block) {
+        return Err(ContractError::DeadlineNotReached {});
+    }
+
+    let game_match = match game_found.match_id {
+        Some(match_id) => Some(MATCHES.load(deps.storage, match_id)?),
+        None => None,
+    };
+
+    // only the party that is not stalling the game may claim it. An opponent who
+    // stops responding in the middle of a match forfeits the match.
+    let result = match game_found.status {
+        GameStatus::AwaitingResponse if info.sender == game_found.host => match &game_match {
+            Some(game_match) if !game_match.rounds.is_empty() => Some(GameResult::HostWins),
+            _ => None,
+        },
+        GameStatus::AwaitingReveal if Some(&info.sender) == game_found.opponent.as_ref() => {
+            Some(GameResult::OpponentWins)
+        }
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+
+    // a full pause keeps opponents from responding, so no forfeit can be claimed
+    // while it lasts or before the response window that follows it has passed
+    if let Some(GameResult::HostWins) = result {
+        assert_not_paused(deps.storage, PauseScope::All)?;
+        if let Some(grace) = RESPONSE_GRACE.may_load(deps.storage)? {
+            if !grace.is_expired(&env.block) {
+                return Err(ContractError::DeadlineNotReached {});
+            }
+        }
+    }
+
+    // an opponent shut out by invite-only mode could not have responded, so the
+    // match is called off with both stakes returned instead of forfeited
+    if let (Some(GameResult::HostWins), Some(game_match)) = (&result, &game_match) {
+        if !is_invited(deps.storage, &game_match.opponent)? {
+            let payouts = cancel_and_refund(deps.storage, &env, &mut game_found)?;
+            return Ok(Response::new()
+                .add_messages(payouts)
+                .add_attribute("method", "claim_timeout")
+                .add_attribute("result", "Cancelled")
+                .add_attribute("game_id", game_id.to_string()));
+        }
+    }
+
+    let result_string = match result {
+        Some(GameResult::HostWins) => "Host Won",
+        Some(GameResult::OpponentWins) => "Opponent Won",
+        _ => "Cancelled",
+    };
+
+    game_found.result = result.clone();
+    let rating_changes = match game_found.result {
+        Some(_) => finish_game(deps.storage, &env, &mut game_found)?,
+        None => {
+            close_game(deps.storage, &env, &mut game_found, GameStatus::Expired)?;
+            vec![]
+        }
+    };
+
+    let payouts = match game_match {
+        Some(mut game_match) => {
+            let payouts = finish_match(deps.storage, &env, &mut game_match, result)?;
+            if game_match.result.is_some() {
+                MATCHES.save(deps.storage, game_match.id, &game_match)?;
+            }
+            payouts
+        }
+        None => settle_stakes(deps.storage, &env, &game_found)?,
+    };
+
+    Ok(Response::new()
+        .add_messages(payouts)
+        .add_attribute("method", "claim_timeout")
+        .add_attribute("result", result_string)
+        .add_attribute("game_id", game_id.to_string())
+        .add_attributes(rating_changes))
+}
+
+/// Takes a game out of play at the current block, keeping it as history.
+fn close_game(
+    storage: &mut dyn Storage,
+    env: &Env,
+    game: &mut Game,
+    status: GameStatus,
+) -> StdResult<()> {
+    update_global_stats(storage, |stats| {
+        match (&status, &game.result) {
+            (GameStatus::Finished, Some(GameResult::Tie)) => {
+                stats.games_resolved += 1;
+                stats.ties += 1;
+            }
+            (GameStatus::Finished, _) => stats.games_resolved += 1,
+            (GameStatus::Cancelled, _) => stats.games_cancelled += 1,
+            (GameStatus::Expired, _) => stats.games_expired += 1,
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    game.status = status;
+    game.finished_height = Some(env.block.height);
+    game.finished_time = Some(env.block.time);
+    games().save(storage, game.id, game)
+}
+
+fn update_activity<F>(storage: &mut dyn Storage, env: &Env, action: F) -> StdResult<()>
+where
+    F: FnOnce(&mut DailyActivity) -> StdResult<()>,
+{
+    let day = env.block.time.seconds() / SECONDS_PER_DAY;
+    let mut activity = DAILY_ACTIVITY
+        .may_load(storage, day)?
+        .unwrap_or(DailyActivity {
+            day,
+            ..DailyActivity::default()
+        });
+    action(&mut activity)?;
+    DAILY_ACTIVITY.save(storage, day, &activity)
+}
+
+fn update_global_stats<F>(storage: &mut dyn Storage, action: F) -> StdResult<()>
+where
+    F: FnOnce(&mut GlobalStats) -> StdResult<()>,
+{
+    let mut stats = GLOBAL_STATS.may_load(storage)?.unwrap_or_default();
+    action(&mut stats)?;
+    GLOBAL_STATS.save(storage, &stats)
+}
+
+/// Marks a resolved game as finished at the current block and keeps it as history.
+/// Both players' records and ratings are updated; the rating changes are returned
+/// as attributes.
+fn finish_game(storage: &mut dyn Storage, env: &Env, game: &mut Game) -> StdResult<Vec<Attribute>> {
+    close_game(storage, env, game, GameStatus::Finished)?;
+
+    let opponent = game
+        .opponent
+        .as_ref()
+        .ok_or_else(|| StdError::generic_err("Resolved game without an opponent"))?;
+    let result = game
+        .result
+        .as_ref()
+        .ok_or_else(|| StdError::generic_err("Finished game without a result"))?;
+
+    let state = STATE.load(storage)?;
+    let host_rating = rating_of(storage, &state, &game.host)?;
+    let opp_rating = rating_of(storage, &state, opponent)?;
+    let delta = rating_delta(host_rating, opp_rating, state.k_factor, result);
+    let new_host_rating = apply_delta(host_rating, delta);
+    let new_opp_rating = apply_delta(opp_rating, -delta);
+
+    for (player, win, loss, rating) in [
+        (
+            &game.host,
+            GameResult::HostWins,
+            GameResult::OpponentWins,
+            new_host_rating,
+        ),
+        (
+            opponent,
+            GameResult::OpponentWins,
+            GameResult::HostWins,
+            new_opp_rating,
+        ),
+    ] {
+        update_player_stats(storage, player, |stats| {
+            match result {
+                result if *result == win => stats.wins += 1,
+                result if *result == loss => stats.losses += 1,
+                _ => stats.ties += 1,
+            }
+            stats.games_played += 1;
+            stats.last_played_height = env.block.height;
+            stats.rating = Some(rating);
+            Ok(())
+        })?;
+    }
+
+    record_head_to_head(storage, game.id, &game.host, opponent, result)?;
+    record_moves(storage, game, opponent)?;
+
+    let day = env.block.time.seconds() / SECONDS_PER_DAY;
+    let mut new_players = 0;
+    for player in [&game.host, opponent] {
+        if !DAILY_PLAYERS.has(storage, (day, player)) {
+            DAILY_PLAYERS.save(storage, (day, player), &Empty {})?;
+            new_players += 1;
+        }
+    }
+    update_activity(storage, env, |activity| {
+        activity.games_played += 1;
+        activity.unique_players += new_players;
+        Ok(())
+    })?;
+
+    let change = |new: u32, old: u32| (i64::from(new) - i64::from(old)).to_string();
+    Ok(vec![
+        attr("host_rating", new_host_rating.to_string()),
+        attr("host_rating_delta", change(new_host_rating, host_rating)),
+        attr("opp_rating", new_opp_rating.to_string()),
+        attr("opp_rating_delta", change(new_opp_rating, opp_rating)),
+    ])
+}
+
+/// Counts the moves of a finished game, globally and for both players.
+fn record_moves(storage: &mut dyn Storage, game: &Game, opponent: &Addr) -> StdResult<()> {
+    let winning_move = match game.result {
+        Some(GameResult::HostWins) => game.host_move,
+        Some(GameResult::OpponentWins) => game.opp_move,
+        _ => None,
+    };
+
+    let mut global = MOVE_STATS.may_load(storage)?.unwrap_or_default();
+    let mut host_stats = PLAYER_MOVE_STATS
+        .may_load(storage, &game.host)?
+        .unwrap_or_default();
+    let mut opp_stats = PLAYER_MOVE_STATS
+        .may_load(storage, opponent)?
+        .unwrap_or_default();
+
+    if let Some(host_move) = game.host_move {
+        global.as_host.add(host_move);
+        host_stats.as_host.add(host_move);
+    }
+    if let Some(opp_move) = game.opp_move {
+        global.as_opponent.add(opp_move);
+        opp_stats.as_opponent.add(opp_move);
+    }
+    if let Some(winning_move) = winning_move {
+        global.wins.add(winning_move);
+        match game.result {
+            Some(GameResult::HostWins) => host_stats.wins.add(winning_move),
+            _ => opp_stats.wins.add(winning_move),
+        }
+    }
+
+    MOVE_STATS.save(storage, &global)?;
+    PLAYER_MOVE_STATS.save(storage, &game.host, &host_stats)?;
+    PLAYER_MOVE_STATS.save(storage, opponent, &opp_stats)
+}
+
+fn record_head_to_head(
+    storage: &mut dyn Storage,
+    game_id: u64,
+    host: &Addr,
+    opponent: &Addr,
+    result: &GameResult,
+) -> StdResult<()> {
+    let winner = match result {
+        GameResult::HostWins => Some(host),
+        GameResult::OpponentWins => Some(opponent),
+        GameResult::Tie => None,
+    };
+    let (first, second) = if host < opponent {
+        (host, opponent)
+    } else {
+        (opponent, host)
+    };
+
+    let mut record = HEAD_TO_HEAD
+        .may_load(storage, (first, second))?
+        .unwrap_or_default();
+
+    match winner {
+        Some(winner) if winner == first => record.first_wins += 1,
+        Some(_) => record.second_wins += 1,
+        None => record.ties += 1,
+    }
+
+    if winner.is_some() && record.streak_holder.as_ref() == winner {
+        record.streak += 1;
+    } else {
+        record.streak_holder = winner.cloned();
+        record.streak = winner.map_or(0, |_| 1);
+    }
+
+    record.recent.insert(
+        0,
+        HeadToHeadGame {
+            game_id,
+            winner: winner.cloned(),
+        },
+    );
+    record.recent.truncate(HEAD_TO_HEAD_RECENT);
+
+    HEAD_TO_HEAD.save(storage, (first, second), &record)
+}
+
+fn rating_of(storage: &dyn Storage, state: &State, player: &Addr) -> StdResult<u32> {
+    let stats = PLAYER_STATS.may_load(storage, player)?;
+    Ok(stats
+        .and_then(|stats| stats.rating)
+        .unwrap_or(state.default_rating))
+}
+
+/// Expected score in basis points of a player rated `rating` against `opponent`,
+/// interpolated from `ELO_EXPECTED_BPS`.
+fn expected_score_bps(rating: u32, opponent: u32) -> i64 {
+    let gap = (i64::from(rating) - i64::from(opponent)).abs().min(800);
+    let step = (gap / 25) as usize;
+    let low = ELO_EXPECTED_BPS[step];
+    let high = ELO_EXPECTED_BPS[(step + 1).min(ELO_EXPECTED_BPS.len() - 1)];
+    let expected = low - (low - high) * (gap % 25) / 25;
+
+    if rating >= opponent {
+        10_000 - expected
+    } else {
+        expected
+    }
+}
+
+/// Rating change of the host; the opponent moves by the same amount the other way.
+fn rating_delta(host_rating: u32, opp_rating: u32, k_factor: u32, result: &GameResult) -> i64 {
+    let score = match result {
+        GameResult::HostWins => 10_000,
+        GameResult::OpponentWins => 0,
+        GameResult::Tie => 5_000,
+    };
+    i64::from(k_factor) * (score - expected_score_bps(host_rating, opp_rating)) / 10_000
+}
+
+fn apply_delta(rating: u32, delta: i64) -> u32 {
+    u32::try_from((i64::from(rating) + delta).max(0)).unwrap_or(u32::MAX)
+}
+
+/// Adds the wager and payout of a settled stake to a player's stats.
+fn record_wager(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    stake: &Asset,
+    payout: Uint128,
+) -> StdResult<()> {
+    update_player_stats(storage, player, |stats| {
+        add_asset(&mut stats.wagered, &stake.denom, stake.amount)?;
+        if !payout.is_zero() {
+            add_asset(&mut stats.won, &stake.denom, payout)?;
+        }
+        Ok(())
+    })
+}
+
+/// Applies `action` to a player's stats and moves the player in the rankings.
+fn update_player_stats<F>(storage: &mut dyn Storage, player: &Addr, action: F) -> StdResult<()>
+where
+    F: FnOnce(&mut PlayerStats) -> StdResult<()>,
+{
+    let old = PLAYER_STATS.may_load(storage, player)?;
+    let mut stats = old.clone().unwrap_or_default();
+    action(&mut stats)?;
+
+    match old {
+        Some(old) => set_ranks(storage, player, &old, false)?,
+        None => update_global_stats(storage, |stats| {
+            stats.distinct_players += 1;
+            Ok(())
+        })?,
+    }
+    set_ranks(storage, player, &stats, true)?;
+
+    PLAYER_STATS.save(storage, player, &stats)
+}
+
+/// Adds or removes the leaderboard entries for a player's stats.
+fn set_ranks(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    stats: &PlayerStats,
+    ranked: bool,
+) -> StdResult<()> {
+    let wins = i64::try_from(stats.wins).unwrap_or(i64::MAX);
+    let win_rate = stats.win_rate_bps(MIN_WIN_RATE_GAMES);
+
+    if ranked {
+        RANK_WINS.save(storage, (wins, player), &Empty {})?;
+        if let Some(win_rate) = win_rate {
+            RANK_WIN_RATE.save(storage, (win_rate, player), &Empty {})?;
+        }
+        if let Some(rating) = stats.rating {
+            RANK_RATING.save(storage, (i64::from(rating), player), &Empty {})?;
+        }
+    } else {
+        RANK_WINS.remove(storage, (wins, player));
+        if let Some(win_rate) = win_rate {
+            RANK_WIN_RATE.remove(storage, (win_rate, player));
+        }
+        if let Some(rating) = stats.rating {
+            RANK_RATING.remove(storage, (i64::from(rating), player));
+        }
+    }
+
+    // every denom a player was paid out in was also wagered
+    for asset in &stats.wagered {
+        let key = denom_key(&asset.denom);
+        let net = stats.net_winnings(&asset.denom);
+        if ranked {
+            RANK_NET_WINNINGS.save(storage, (&key, net, player), &Empty {})?;
+        } else {
+            RANK_NET_WINNINGS.remove(storage, (&key, net, player));
+        }
+    }
+
+    Ok(())
+}
+
+fn add_asset(assets: &mut Vec<Asset>, denom: &Denom, amount: Uint128) -> StdResult<()> {
+    match assets.iter_mut().find(|asset| &asset.denom == denom) {
+        Some(asset) => asset.amount = asset.amount.checked_add(amount)?,
+        None => assets.push(Asset {
+            denom: denom.clone(),
+            amount,
+        }),
+    }
+    Ok(())
+}
+
+/// Native funds sent along with a message, at most a single coin is accepted as a wager.
+fn native_stake(funds: &[Coin]) -> Result<Option<Asset>, ContractError> {
+    match funds {
+        [] => Ok(None),
+        [coin] if !coin.amount.is_zero() => Ok(Some(Asset {
+            denom: Denom::Native(coin.denom.clone()),
+            amount: coin.amount,
+        })),
+        _ => Err(ContractError::InvalidStake {}),
+    }
+}
+
+fn assert_stake_matches(stake: &Option<Asset>, sent: &Option<Asset>) -> Result<(), ContractError> {
+    match (stake, sent) {
+        (None, None) => Ok(()),
+        (Some(expected), None) => Err(ContractError::MissingStake {
+            expected: expected.to_string(),
+        }),
+        (Some(expected), Some(sent)) if expected == sent => Ok(()),
+        _ => Err(ContractError::StakeMismatch {
+            expected: stake
+                .as_ref()
+                .map_or_else(|| "none".to_string(), |a| a.to_string()),
+            received: sent
+                .as_ref()
+                .map_or_else(|| "none".to_string(), |a| a.to_string()),
+        }),
+    }
+}
+
+fn transfer(denom: &Denom, amount: Uint128, to: &Addr) -> StdResult<CosmosMsg> {
+    let msg = match denom {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into(),
+        Denom::Cw20(contract) => WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: to.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+    Ok(msg)
+}
+
+/// Transfers releasing the escrowed stakes of a game that is being closed.
+fn settle_stakes(storage: &mut dyn Storage, env: &Env, game: &Game) -> StdResult<Vec<CosmosMsg>> {
+    settle(
+        storage,
+        env,
+        &game.host,
+        game.opponent.as_ref(),
+        game.stake.as_ref(),
+        game.result.as_ref(),
+    )
+}
+
+/// Without a result only the host has paid in, so the host is refunded. Resolved
+/// games pay the house fee out of each stake into `FEES`.
+fn settle(
+    storage: &mut dyn Storage,
+    env: &Env,
+    host: &Addr,
+    opponent: Option<&Addr>,
+    stake: Option<&Asset>,
+    result: Option<&GameResult>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let stake = match stake {
+        Some(stake) => stake,
+        None => return Ok(vec![]),
+    };
+
+    let send = |to: &Addr, amount: Uint128| transfer(&stake.denom, amount, to);
+
+    let result = match result {
+        Some(result) => result,
+        None => return Ok(vec![send(host, stake.amount)?]),
+    };
+
+    let opponent =
+        opponent.ok_or_else(|| StdError::generic_err("Resolved game without an opponent"))?;
+
+    let fee_bps = STATE.load(storage)?.fee_bps;
+    let fee = stake
+        .amount
+        .multiply_ratio(fee_bps, MAX_FEE_BPS)
+        .checked_mul(Uint128::new(2))?;
+
+    if !fee.is_zero() {
+        let key = denom_key(&stake.denom);
+        FEES.update(storage, &key, |accrued| -> StdResult<_> {
+            let mut accrued = accrued.unwrap_or(Asset {
+                denom: stake.denom.clone(),
+                amount: Uint128::zero(),
+            });
+            accrued.amount = accrued.amount.checked_add(fee)?;
+            Ok(accrued)
+        })?;
+    }
+
+    update_global_stats(storage, |stats| {
+        add_asset(
+            &mut stats.volume,
+            &stake.denom,
+            stake.amount.checked_mul(Uint128::new(2))?,
+        )?;
+        if !fee.is_zero() {
+            add_asset(&mut stats.fees_collected, &stake.denom, fee)?;
+        }
+        Ok(())
+    })?;
+    update_activity(storage, env, |activity| {
+        add_asset(
+            &mut activity.volume,
+            &stake.denom,
+            stake.amount.checked_mul(Uint128::new(2))?,
+        )
+    })?;
+
+    let pot = stake
+        .amount
+        .checked_mul(Uint128::new(2))?
+        .checked_sub(fee)?;
+
+    let (host_payout, opp_payout) = match result {
+        GameResult::Tie => {
+            let refund = pot.multiply_ratio(1u128, 2u128);
+            (refund, refund)
+        }
+        GameResult::HostWins => (pot, Uint128::zero()),
+        GameResult::OpponentWins => (Uint128::zero(), pot),
+    };
+
+    record_wager(storage, host, stake, host_payout)?;
+    record_wager(storage, opponent, stake, opp_payout)?;
+
+    let mut payouts = vec![];
+    for (player, payout) in [(host, host_payout), (opponent, opp_payout)] {
+        if !payout.is_zero() {
+            payouts.push(send(player, payout)?);
+        }
+    }
+    Ok(payouts)
+}
+
+/// Fails when the current pause reaches as far as `scope`; a pause of everything
+/// blocks every scope.
+fn assert_not_paused(storage: &dyn Storage, scope: PauseScope) -> Result<(), ContractError> {
+    match PAUSE.may_load(storage)? {
+        Some(pause) if pause.scope == PauseScope::All || pause.scope == scope => {
+            Err(ContractError::Paused {})
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails for an address missing from the player allowlist while the contract is
+/// invite-only.
+fn assert_invited(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if !is_invited(storage, address)? {
+        return Err(ContractError::NotAllowlisted {});
+    }
+    Ok(())
+}
+
+fn is_invited(storage: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    Ok(!STATE.load(storage)?.invite_only || PLAYER_ALLOWLIST.has(storage, address))
+}
+
+/// Returns the stake to each player that paid it in, without taking a fee.
+fn refund_stakes(
+    host: &Addr,
+    opponent: Option<&Addr>,
+    stake: Option<&Asset>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let stake = match stake {
+        Some(stake) => stake,
+        None => return Ok(vec![]),
+    };
+
+    std::iter::once(host)
+        .chain(opponent)
+        .map(|player| transfer(&stake.denom, stake.amount, player))
+        .collect()
+}
+
+fn is_blacklisted(deps: Deps, env: &Env, address: &Addr) -> StdResult<bool> {
+    Ok(match BLACKLIST.may_load(deps.storage, address)? {
+        Some(BlacklistEntry {
+            expires: Some(expires),
+            ..
+        }) => !expires.is_expired(&env.block),
+        Some(_) => true,
+        None => false,
+    })
+}
+
+fn game_result(host_move: GameMove, opp_move: GameMove) -> GameResult {
+    if host_move == GameMove::Rock && opp_move == GameMove::Scissors
+        || host_move == GameMove::Paper && opp_move == GameMove::Rock
+        || host_move == GameMove::Scissors && opp_move == GameMove::Paper
+    {
+        GameResult::HostWins
+    } else if host_move == opp_move {
+        GameResult::Tie
+    } else {
+        GameResult::OpponentWins
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ListGames {
+            status,
+            player,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_list_games(
+            deps,
+            status,
+            player,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetOwner {} => to_binary(&query_owner(deps)?),
+        QueryMsg::GetGamesByHost {
+            address,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_game_by_host(
+            deps,
+            address,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetGamesByOpponent {
+            opponent,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_game_by_opp(
+            deps,
+            opponent,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetGame { game_id } => to_binary(&query_game(deps, game_id)?),
+        QueryMsg::GetPlayerHistory {
+            player,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_player_history(
+            deps,
+            player,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetAdmin {} => to_binary(&query_admin(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::GetPauseStatus {} => to_binary(&query_pause_status(deps)?),
+        QueryMsg::GetCw20Allowlist { start_after, limit } => {
+            to_binary(&query_cw20_allowlist(deps, start_after, limit)?)
+        }
+        QueryMsg::ListAllowlist { start_after, limit } => {
+            to_binary(&query_allowlist(deps, start_after, limit)?)
+        }
+        QueryMsg::IsAllowlisted { address } => to_binary(&query_is_allowlisted(deps, address)?),
+        QueryMsg::ListBlacklist { start_after, limit } => {
+            to_binary(&query_blacklist(deps, start_after, limit)?)
+        }
+        QueryMsg::GetAccruedFees { start_after, limit } => {
+            to_binary(&query_accrued_fees(deps, start_after, limit)?)
+        }
+        QueryMsg::GetOpenChallenges {
+            stake,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&query_open_challenges(
+            deps,
+            stake,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetMatch { match_id } => to_binary(&query_match(deps, match_id)?),
+        QueryMsg::GetPlayerStats { address } => to_binary(&query_player_stats(deps, address)?),
+        QueryMsg::GetGlobalStats {} => to_binary(&query_global_stats(deps)?),
+        QueryMsg::GetActivity { from, to } => to_binary(&query_activity(deps, from, to)?),
+        QueryMsg::GetMoveStats { address } => to_binary(&query_move_stats(deps, address)?),
+        QueryMsg::GetRating { address } => to_binary(&query_rating(deps, address)?),
+        QueryMsg::GetPlayerStatsBatch { addresses } => {
+            to_binary(&query_player_stats_batch(deps, addresses)?)
+        }
+        QueryMsg::GetHeadToHead { a, b } => to_binary(&query_head_to_head(deps, a, b)?),
+        QueryMsg::GetLeaderboard {
+            metric,
+            start_after,
+            limit,
+        } => to_binary(&query_leaderboard(deps, metric, start_after, limit)?),
+    }
+}
+
+fn query_owner(deps: Deps) -> StdResult<Addr> {
+    let state = STATE.load(deps.storage)?;
+    Ok(state.owner)
+}
+
+fn query_config(deps: Deps) -> StdResult<State> {
+    STATE.load(deps.storage)
+}
+
+fn query_pause_status(deps: Deps) -> StdResult<Option<PauseInfo>> {
+    PAUSE.may_load(deps.storage)
+}
+
+fn query_game(deps: Deps, game_id: u64) -> StdResult<Game> {
+    let game = games().may_load(deps.storage, game_id)?;
+
+    match game {
+        Some(g) => Ok(g),
+        None => Err(StdError::generic_err("Game not found")),
+    }
+}
+
+fn query_match(deps: Deps, match_id: u64) -> StdResult<Match> {
+    MATCHES
+        .may_load(deps.storage, match_id)?
+        .ok_or_else(|| StdError::generic_err("Match not found"))
+}
+
+fn query_player_stats(deps: Deps, address: Addr) -> StdResult<PlayerStats> {
+    let validated_addr = deps.api.addr_validate(address.as_str())?;
+
+    Ok(PLAYER_STATS
+        .may_load(deps.storage, &validated_addr)?
+        .unwrap_or_default())
+}
+
+fn query_global_stats(deps: Deps) -> StdResult<GlobalStats> {
+    Ok(GLOBAL_STATS.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn query_activity(deps: Deps, from: u64, to: u64) -> StdResult<ActivityResponse> {
+    if from > to || to - from >= MAX_ACTIVITY_DAYS {
+        return Err(StdError::generic_err(format!(
+            "Activity range must span between 1 and {} days",
+            MAX_ACTIVITY_DAYS
+        )));
+    }
+
+    let days: StdResult<Vec<_>> = DAILY_ACTIVITY
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(from)),
+            Some(Bound::inclusive(to)),
+            Order::Ascending,
+        )
+        .map(|item| item.map(|(_, activity)| activity))
+        .collect();
+
+    Ok(ActivityResponse { days: days? })
+}
+
+fn query_move_stats(deps: Deps, address: Option<Addr>) -> StdResult<MoveStats> {
+    let stats = match address {
+        Some(address) => {
+            let validated_addr = deps.api.addr_validate(address.as_str())?;
+            PLAYER_MOVE_STATS.may_load(deps.storage, &validated_addr)?
+        }
+        None => MOVE_STATS.may_load(deps.storage)?,
+    };
+
+    Ok(stats.unwrap_or_default())
+}
+
+fn query_rating(deps: Deps, address: Addr) -> StdResult<u32> {
+    let validated_addr = deps.api.addr_validate(address.as_str())?;
+    let state = STATE.load(deps.storage)?;
+
+    rating_of(deps.storage, &state, &validated_addr)
+}
+
+fn query_player_stats_batch(
+    deps: Deps,
+    addresses: Vec<Addr>,
+) -> StdResult<PlayerStatsBatchResponse> {
+    if addresses.len() > MAX_LIMIT as usize {
+        return Err(StdError::generic_err("Too many addresses"));
+    }
+
+    let stats: StdResult<Vec<_>> = addresses
+        .into_iter()
+        .map(|address| {
+            Ok(PlayerStatsEntry {
+                stats: query_player_stats(deps, address.clone())?,
+                address,
+            })
+        })
+        .collect();
+
+    Ok(PlayerStatsBatchResponse { stats: stats? })
+}
+
+fn query_head_to_head(deps: Deps, a: Addr, b: Addr) -> StdResult<HeadToHeadResponse> {
+    let a = deps.api.addr_validate(a.as_str())?;
+    let b = deps.api.addr_validate(b.as_str())?;
+
+    let (first, second) = if a < b { (&a, &b) } else { (&b, &a) };
+    let record = HEAD_TO_HEAD
+        .may_load(deps.storage, (first, second))?
+        .unwrap_or_default();
+
+    let (a_wins, b_wins) = if first == &a {
+        (record.first_wins, record.second_wins)
+    } else {
+        (record.second_wins, record.first_wins)
+    };
+
+    Ok(HeadToHeadResponse {
+        a_wins,
+        b_wins,
+        ties: record.ties,
+        recent: record.recent,
+        streak_holder: record.streak_holder,
+        streak: record.streak,
+    })
+}
+
+fn query_leaderboard(
+    deps: Deps,
+    metric: LeaderboardMetric,
+    start_after: Option<LeaderboardEntry>,
+    limit: Option<u32>,
+) -> StdResult<LeaderboardResponse> {
+    let limit = page_limit(limit);
+
+    let start_after = match start_after {
+        Some(entry) => Some((entry.score, deps.api.addr_validate(entry.address.as_str())?)),
+        None => None,
+    };
+    let max = start_after
+        .as_ref()
+        .map(|(score, address)| Bound::exclusive((*score, address)));
+
+    let ranked: StdResult<Vec<(i64, Addr)>> = match &metric {
+        LeaderboardMetric::Wins => RANK_WINS
+            .keys(deps.storage, None, max, Order::Descending)
+            .take(limit)
+            .collect(),
+        LeaderboardMetric::WinRate => RANK_WIN_RATE
+            .keys(deps.storage, None, max, Order::Descending)
+            .take(limit)
+            .collect(),
+        LeaderboardMetric::Rating => RANK_RATING
+            .keys(deps.storage, None, max, Order::Descending)
+            .take(limit)
+            .collect(),
+        LeaderboardMetric::NetWinnings { denom } => RANK_NET_WINNINGS
+            .sub_prefix(&denom_key(denom))
+            .keys(deps.storage, None, max, Order::Descending)
+            .take(limit)
+            .collect(),
+    };
+
+    let entries: Vec<LeaderboardEntry> = ranked?
+        .into_iter()
+        .map(|(score, address)| LeaderboardEntry { address, score })
+        .collect();
+
+    let next_start_after = match entries.len() == limit {
+        true => entries.last().cloned(),
+        false => None,
+    };
+
+    Ok(LeaderboardResponse {
+        entries,
+        next_start_after,
+    })
+}
+
+fn query_game_by_host(
+    deps: Deps,
+    address: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GamesListResponse> {
+    let validated_addr = deps.api.addr_validate(address.as_str())?;
+    let order = order.map_or(Order::Ascending, Order::from);
+
+    let prefixes = GameStatus::ACTIVE
+        .iter()
+        .map(|status| {
+            games()
+                .idx
+                .host_status
+                .prefix((validated_addr.clone(), status.as_str().to_string()))
+        })
+        .collect();
+
+    merged_games_page(deps, prefixes, start_after, page_limit(limit), order)
+}
+
+fn query_game_by_opp(
+    deps: Deps,
+    opponent: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GamesListResponse> {
+    let validated_addr = deps.api.addr_validate(opponent.as_str())?;
+    let order = order.map_or(Order::Ascending, Order::from);
+
+    let prefixes = GameStatus::ACTIVE
+        .iter()
+        .map(|status| {
+            games()
+                .idx
+                .opponent_status
+                .prefix((validated_addr.to_string(), status.as_str().to_string()))
+        })
+        .collect();
+
+    merged_games_page(deps, prefixes, start_after, page_limit(limit), order)
+}
+
+fn query_player_history(
+    deps: Deps,
+    player: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GamesListResponse> {
+    let validated_addr = deps.api.addr_validate(player.as_str())?;
+    let order = order.map_or(Order::Ascending, Order::from);
+
+    player_games_by_status(
+        deps,
+        &validated_addr,
+        &GameStatus::CLOSED,
+        start_after,
+        page_limit(limit),
+        order,
+    )
+}
+
+fn query_list_games(
+    deps: Deps,
+    status: Option<GameStatus>,
+    player: Option<Addr>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GamesListResponse> {
+    let order = order.map_or(Order::Ascending, Order::from);
+    let limit = page_limit(limit);
+
+    if let Some(player) = player {
+        let validated_addr = deps.api.addr_validate(player.as_str())?;
+        return match status {
+            Some(status) => {
+                player_games_by_status(deps, &validated_addr, &[status], start_after, limit, order)
+            }
+            None => {
+                let prefixes = vec![
+                    games().idx.host.prefix(validated_addr.clone()),
+                    games().idx.opponent.prefix(validated_addr.to_string()),
+                ];
+                merged_games_page(deps, prefixes, start_after, limit, order)
+            }
+        };
+    }
+
+    let (min, max) = page_bounds(start_after, order);
+    match status {
+        Some(status) => {
+            let games_queried = games()
+                .idx
+                .status
+                .prefix(status.as_str().to_string())
+                .range(deps.storage, min, max, order);
+            games_page(games_queried, limit)
+        }
+        None => games_page(games().range(deps.storage, min, max, order), limit),
+    }
+}
+
+/// Games `player` hosts or plays as opponent in one of `statuses`, read from the
+/// (player, status) indexes so other games are never visited.
+fn player_games_by_status(
+    deps: Deps,
+    player: &Addr,
+    statuses: &[GameStatus],
+    start_after: Option<u64>,
+    limit: usize,
+    order: Order,
+) -> StdResult<GamesListResponse> {
+    let mut prefixes = vec![];
+    for status in statuses {
+        let status = status.as_str().to_string();
+        prefixes.push(
+            games()
+                .idx
+                .host_status
+                .prefix((player.clone(), status.clone())),
+        );
+        prefixes.push(
+            games()
+                .idx
+                .opponent_status
+                .prefix((player.to_string(), status)),
+        );
+    }
+
+    merged_games_page(deps, prefixes, start_after, limit, order)
+}
+
+/// Takes a full page from each index prefix and merges them back into id order.
+fn merged_games_page(
+    deps: Deps,
+    prefixes: Vec<Prefix<u64, Game, u64>>,
+    start_after: Option<u64>,
+    limit: usize,
+    order: Order,
+) -> StdResult<GamesListResponse> {
+    let mut games_found = vec![];
+    for prefix in prefixes {
+        let (min, max) = page_bounds(start_after, order);
+        let page: StdResult<Vec<_>> = prefix
+            .range(deps.storage, min, max, order)
+            .take(limit)
+            .collect();
+        games_found.extend(page?);
+    }
+
+    games_found.sort_by_key(|(id, _)| *id);
+    if let Order::Descending = order {
+        games_found.reverse();
+    }
+
+    games_page(games_found.into_iter().map(Ok), limit)
+}
+
+fn query_open_challenges(
+    deps: Deps,
+    stake: Option<Asset>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GamesListResponse> {
+    let order = order.map_or(Order::Ascending, Order::from);
+    let (min, max) = page_bounds(start_after, order);
+
+    let status = GameStatus::AwaitingResponse.as_str().to_string();
+    let open_challenges = match stake {
+        Some(stake) => games().idx.opponent_status_stake.prefix((
+            String::new(),
+            status,
+            stake_key(Some(&stake)),
+        )),
+        None => games().idx.opponent_status.prefix((String::new(), status)),
+    };
+
+    games_page(
+        open_challenges.range(deps.storage, min, max, order),
+        page_limit(limit),
+    )
+}
+
+fn query_cw20_allowlist(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Cw20AllowlistResponse> {
+    let limit = page_limit(limit);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let contracts: StdResult<Vec<Addr>> = CW20_ALLOWLIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+    let contracts = contracts?;
+
+    let next_start_after = match contracts.len() == limit {
+        true => contracts.last().cloned(),
+        false => None,
+    };
+
+    Ok(Cw20AllowlistResponse {
+        contracts,
+        next_start_after,
+    })
+}
+
+fn query_allowlist(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<AllowlistResponse> {
+    let limit = page_limit(limit);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses: StdResult<Vec<Addr>> = PLAYER_ALLOWLIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+    let addresses = addresses?;
+
+    let next_start_after = match addresses.len() == limit {
+        true => addresses.last().cloned(),
+        false => None,
+    };
+
+    Ok(AllowlistResponse {
+        addresses,
+        next_start_after,
+    })
+}
+
+fn query_is_allowlisted(deps: Deps, address: Addr) -> StdResult<bool> {
+    let validated_addr = deps.api.addr_validate(address.as_str())?;
+
+    Ok(PLAYER_ALLOWLIST.has(deps.storage, &validated_addr))
+}
+
+fn query_blacklist(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<BlacklistResponse> {
+    let limit = page_limit(limit);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let entries: StdResult<Vec<BlacklistEntryResponse>> = BLACKLIST
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(address, entry)| BlacklistEntryResponse { address, entry }))
+        .collect();
+    let entries = entries?;
+
+    let next_start_after = match entries.len() == limit {
+        true => entries.last().map(|entry| entry.address.clone()),
+        false => None,
+    };
+
+    Ok(BlacklistResponse {
+        entries,
+        next_start_after,
+    })
+}
+
+fn query_accrued_fees(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FeesResponse> {
+    let limit = page_limit(limit);
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let fees: StdResult<Vec<(String, Asset)>> = FEES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+    let fees = fees?;
+
+    let next_start_after = match fees.len() == limit {
+        true => fees.last().map(|(key, _)| key.clone()),
+        false => None,
+    };
+
+    Ok(FeesResponse {
+        fees: fees.into_iter().map(|(_, fee)| fee).collect(),
+        next_start_after,
+    })
+}
+
+fn page_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize
+}
+
+/// Range bounds that resume a listing after `start_after` in the given order.
+fn page_bounds<'a>(
+    start_after: Option<u64>,
+    order: Order,
+) -> (Option<Bound<'a, u64>>, Option<Bound<'a, u64>>) {
+    let start = start_after.map(Bound::exclusive);
+    match order {
+        Order::Ascending => (start, None),
+        Order::Descending => (None, start),
+    }
+}
+
+/// Collects a page of at most `limit` games, with a cursor when the page is full.
+fn games_page(
+    games_queried: impl Iterator<Item = StdResult<(u64, Game)>>,
+    limit: usize,
+) -> StdResult<GamesListResponse> {
+    let games_found: StdResult<Vec<Game>> = games_queried
+        .take(limit)
+        .map(|item| item.map(|(_, game)| game))
+        .collect();
+    let games_found = games_found?;
+
+    let next_start_after = match games_found.len() == limit {
+        true => games_found.last().map(|game| game.id),
+        false => None,
+    };
+
+    Ok(GamesListResponse {
+        games: games_found,
+        next_start_after,
+    })
+}
+
+fn query_admin(deps: Deps) -> StdResult<Option<Addr>> {
+    ADMIN.get(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::MoveCounts;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, SubMsg};
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query owner
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator"), value);
+    }
+
+    #[test]
+    fn query_games_by_host() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 1st opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 2nd opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("second_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Paper, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 3rd opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("third_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Scissors, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query games by host address
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost {
+                address: Addr::unchecked("creator"),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+
+        let value: GamesListResponse = from_binary(&res).unwrap();
+
+        assert_eq!(3, value.games.len());
+
+        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
+        assert_eq!(
+            Some(Addr::unchecked("first_player")),
+            value.games[0].opponent
+        );
+        assert_eq!(None, value.games[0].host_move);
+        assert_eq!(GameStatus::AwaitingResponse, value.games[0].status);
+        assert_eq!(None, value.games[0].opp_move);
+        assert_eq!(None, value.games[0].result);
+
+        assert_eq!(Addr::unchecked("creator"), value.games[1].host);
+        assert_eq!(
+            Some(Addr::unchecked("second_player")),
+            value.games[1].opponent
+        );
+        assert_eq!(None, value.games[1].host_move);
+        assert_eq!(GameStatus::AwaitingResponse, value.games[1].status);
+        assert_eq!(None, value.games[1].opp_move);
+        assert_eq!(None, value.games[1].result);
+    }
+
+    #[test]
+    fn query_games_by_opp() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("other_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query games by opponent address
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByOpponent {
+                opponent: Addr::unchecked("other_player"),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+
+        let value: GamesListResponse = from_binary(&res).unwrap();
+
+        assert_eq!(Addr::unchecked("creator"), value.games[0].host);
+        assert_eq!(
+            Some(Addr::unchecked("other_player")),
+            value.games[0].opponent
+        );
+        assert_eq!(None, value.games[0].host_move);
+        assert_eq!(GameStatus::AwaitingResponse, value.games[0].status);
+        assert_eq!(None, value.games[0].opp_move);
+        assert_eq!(None, value.games[0].result);
+    }
+
+    #[test]
+    fn query_game_by_opp_and_host() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("other_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query games by host and opponent addresses - fail
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 2 });
+
+        match res {
+            Err(_std_error) => {}
+            _ => panic!("Must return Game not found error"),
+        }
+
+        // query games by host and opponent addresses - success
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+
+        let value: Game = from_binary(&res).unwrap();
+
+        assert_eq!(Addr::unchecked("creator"), value.host);
+        assert_eq!(Some(Addr::unchecked("other_player")), value.opponent);
+        assert_eq!(None, value.host_move);
+        assert_eq!(GameStatus::AwaitingResponse, value.status);
+        assert_eq!(None, value.opp_move);
+        assert_eq!(None, value.result);
+    }
+
+    #[test]
+    fn get_admin() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator_man", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query admin success
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator_man"), value);
+    }
+
+    #[test]
+    fn update_admin() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator_man", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query 1st admin success
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator_man"), value);
+
+        // execute admin update w/ funds attached - should error
+        let info = mock_info("creator_man", &coins(2, "token"));
+        let msg = ExecuteMsg::UpdateAdmin {
+            admin: Addr::unchecked("updated_man"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+
+        match res {
+            Err(ContractError::UnexpectedFunds {}) => {}
+            _ => panic!("Must return UnexpectedFunds error"),
+        }
+
+        // execute admin update
+        let info = mock_info("creator_man", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query 2nd admin success
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("updated_man"), value);
+    }
+
+    #[test]
+    fn host_blacklist() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute add to blacklist
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("host_black_listed"),
+            reason: None,
+            expires: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ opponent and host move
+        let info = mock_info("host_black_listed", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("other_player")),
+            host_commitment: hash_move(
+                &Addr::unchecked("host_black_listed"),
+                GameMove::Rock,
+                "salt",
+            ),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::HostAddressBlacklisted {}) => {}
+            _ => panic!("Must return BlackListedAddress error"),
+        }
+
+        // execute remove from blacklist
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RemoveFromBlacklist {
+            address: Addr::unchecked("host_black_listed"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ opponent and host move
+        let info = mock_info("host_black_listed", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("other_player")),
+            host_commitment: hash_move(
+                &Addr::unchecked("host_black_listed"),
+                GameMove::Rock,
+                "salt",
+            ),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query games by host address
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost {
+                address: Addr::unchecked("host_black_listed"),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+
+        let value: GamesListResponse = from_binary(&res).unwrap();
+
+        assert_eq!(1, value.games.len());
+
+        assert_eq!(Addr::unchecked("host_black_listed"), value.games[0].host);
+        assert_eq!(
+            Some(Addr::unchecked("other_player")),
+            value.games[0].opponent
+        );
+        assert_eq!(None, value.games[0].host_move);
+        assert_eq!(GameStatus::AwaitingResponse, value.games[0].status);
+        assert_eq!(None, value.games[0].opp_move);
+        assert_eq!(None, value.games[0].result);
+    }
+
+    #[test]
+    fn full_game_tie() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 1st opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute try response from non-opponent - should end with error
+        let info = mock_info("second_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Rock,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        // should error
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // execute try response from opponent - should be success
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Rock,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(res.attributes[0].key, "method");
+        assert_eq!(res.attributes[0].value, "response");
+
+        // execute reveal from host - should be success
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        println!("{:?}", res);
+
+        assert_eq!(res.attributes[0].key, "method");
+        assert_eq!(res.attributes[0].value, "reveal");
+        assert_eq!(res.attributes[1].key, "result");
+        assert_eq!(res.attributes[1].value, "Tie");
+
+        // both stakes are refunded
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "creator".to_string(),
+                    amount: coins(2, "token"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "first_player".to_string(),
+                    amount: coins(2, "token"),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn full_game_host_wins() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 1st opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute try response from opponent - should be success
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Scissors,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(res.attributes[0].key, "method");
+        assert_eq!(res.attributes[0].value, "response");
+
+        // execute reveal from host - should be success
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        println!("{:?}", res);
+
+        assert_eq!(res.attributes[0].key, "method");
+        assert_eq!(res.attributes[0].value, "reveal");
+        assert_eq!(res.attributes[1].key, "result");
+        assert_eq!(res.attributes[1].value, "Host Won");
+
+        // host takes the pot
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(4, "token"),
+            })]
+        );
+    }
+
+    #[test]
+    fn full_game_opp_wins() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 1st opponent and host move
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute try response from opponent - should be success
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(res.attributes[0].key, "method");
+        assert_eq!(res.attributes[0].value, "response");
+
+        // execute reveal from host - should be success
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        println!("{:?}", res);
+
+        assert_eq!(res.attributes[0].key, "method");
+        assert_eq!(res.attributes[0].value, "reveal");
+        assert_eq!(res.attributes[1].key, "result");
+        assert_eq!(res.attributes[1].value, "Opponent Won");
+
+        // opponent takes the pot
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "first_player".to_string(),
+                amount: coins(4, "token"),
+            })]
+        );
+    }
+
+    #[test]
+    fn reveal_must_match_commitment() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 1st opponent and host commitment
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute reveal before the opponent responded - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::AwaitingResponse {}) => {}
+            _ => panic!("Must return AwaitingResponse error"),
+        }
+
+        // execute try response from opponent - should be success
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // responding twice - should error
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Scissors,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::AlreadyResponded {}) => {}
+            _ => panic!("Must return AlreadyResponded error"),
+        }
+
+        // execute reveal w/ a different move than committed - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Scissors,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::InvalidReveal {}) => {}
+            _ => panic!("Must return InvalidReveal error"),
+        }
+
+        // the game still awaits a valid reveal
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+
+        let value: Game = from_binary(&res).unwrap();
+
+        assert_eq!(GameStatus::AwaitingReveal, value.status);
+        assert_eq!(None, value.host_move);
+        assert_eq!(Some(GameMove::Paper), value.opp_move);
+    }
+
+    #[test]
+    fn claim_timeout() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            response_timeout: Some(Duration::Height(10)),
+            reveal_timeout: Some(Duration::Height(5)),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ 1st opponent and host commitment
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute claim before the response deadline - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::DeadlineNotReached {}) => {}
+            _ => panic!("Must return DeadlineNotReached error"),
+        }
+
+        // execute claim from the stalling opponent - should error
+        let mut env = mock_env();
+        env.block.height += 10;
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // execute claim from the host - game gets cancelled
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(res.attributes[0].value, "claim_timeout");
+        assert_eq!(res.attributes[1].key, "result");
+        assert_eq!(res.attributes[1].value, "Cancelled");
+
+        // host gets the stake back
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(2, "token"),
+            })]
+        );
+
+        // start a new game and let the host stall the reveal
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 2,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height += 5;
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 2 };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(res.attributes[1].key, "result");
+        assert_eq!(res.attributes[1].value, "Opponent Won");
+
+        // the claimed game is kept as finished
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 2 }).unwrap();
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(GameStatus::Finished, value.status);
+        assert_eq!(Some(GameResult::OpponentWins), value.result);
+
+        // execute claim of the finished game again - should error
+        let mut env = mock_env();
+        env.block.height += 5;
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 2 };
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        match res {
+            Err(ContractError::GameEnded {}) => {}
+            _ => panic!("Must return GameEnded error"),
+        }
+    }
+
+    #[test]
+    fn update_config() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute update config from non-admin - should error
+        let info = mock_info("random_person", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            response_timeout: Some(Duration::Height(100)),
+            reveal_timeout: None,
+            fee_bps: None,
+            default_rating: None,
+            k_factor: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::Admin(_)) => {}
+            _ => panic!("Must return Admin error"),
+        }
+
+        // execute update config from admin - should be success
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            response_timeout: Some(Duration::Height(100)),
+            reveal_timeout: None,
+            fee_bps: None,
+            default_rating: None,
+            k_factor: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: State = from_binary(&res).unwrap();
+        assert_eq!(Duration::Height(100), value.response_timeout);
+        assert_eq!(DEFAULT_TIMEOUT, value.reveal_timeout);
+    }
+
+    #[test]
+    fn wager_must_match() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game w/ two coins - should error
+        let info = mock_info(
+            "creator",
+            &[Coin::new(2, "token"), Coin::new(2, "other_token")],
+        );
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::InvalidStake {}) => {}
+            _ => panic!("Must return InvalidStake error"),
+        }
+
+        // execute start game w/ a single coin - should be success
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute response w/o funds - should error
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::MissingStake { expected }) => assert_eq!(expected, "2token"),
+            _ => panic!("Must return MissingStake error"),
+        }
+
+        // execute response w/ a smaller amount - should error
+        let info = mock_info("first_player", &coins(1, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::StakeMismatch { expected, received }) => {
+                assert_eq!(expected, "2token");
+                assert_eq!(received, "1token");
+            }
+            _ => panic!("Must return StakeMismatch error"),
+        }
+
+        // execute response w/ the matching stake - should be success
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(
+            Some(Asset {
+                denom: Denom::Native("token".to_string()),
+                amount: Uint128::new(2),
+            }),
+            value.stake
+        );
+    }
+
+    #[test]
+    fn cw20_wager() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let start_msg = to_binary(&ReceiveMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        })
+        .unwrap();
+
+        // execute start game through a cw20 contract not on the allowlist - should error
+        let info = mock_info("cw20_token", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(10),
+            msg: start_msg.clone(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::Cw20NotAllowed {}) => {}
+            _ => panic!("Must return Cw20NotAllowed error"),
+        }
+
+        // execute allow cw20 from non-admin - should error
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::AllowCw20 {
+            contract: Addr::unchecked("cw20_token"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        assert!(res.is_err());
+
+        // execute allow cw20 from admin - should be success
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AllowCw20 {
+            contract: Addr::unchecked("cw20_token"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCw20Allowlist {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: Cw20AllowlistResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("cw20_token")], value.contracts);
+
+        // execute start game through the allowlisted cw20 contract - should be success
+        let info = mock_info("cw20_token", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(10),
+            msg: start_msg,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute response w/ native funds - should error
+        let info = mock_info("first_player", &coins(10, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::StakeMismatch { .. }) => {}
+            _ => panic!("Must return StakeMismatch error"),
+        }
+
+        // execute response through the same cw20 contract - should be success
+        let info = mock_info("cw20_token", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "first_player".to_string(),
+            amount: Uint128::new(10),
+            msg: to_binary(&ReceiveMsg::Respond {
+                game_id: 1,
+                opp_move: GameMove::Paper,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute reveal from host - opponent is paid out in cw20 tokens
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(res.attributes[1].value, "Opponent Won");
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "first_player".to_string(),
+                    amount: Uint128::new(20),
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+    }
+
+    #[test]
+    fn house_fee() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            fee_bps: Some(500),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute update config w/ a fee above 100% - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            response_timeout: None,
+            reveal_timeout: None,
+            fee_bps: Some(10_001),
+            default_rating: None,
+            k_factor: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::InvalidFee {}) => {}
+            _ => panic!("Must return InvalidFee error"),
+        }
+
+        // play a game for 100 token each
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &coins(100, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Scissors,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // host takes the pot minus 5%
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(190, "token"),
+            })]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAccruedFees {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: FeesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Asset {
+                denom: Denom::Native("token".to_string()),
+                amount: Uint128::new(10),
+            }],
+            value.fees
+        );
+
+        // execute withdraw fees from non-admin - should error
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::WithdrawFees {
+            denom: Denom::Native("token".to_string()),
+            amount: None,
+            recipient: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::Admin(_)) => {}
+            _ => panic!("Must return Admin error"),
+        }
+
+        // execute withdraw of more than accrued - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::WithdrawFees {
+            denom: Denom::Native("token".to_string()),
+            amount: Some(Uint128::new(11)),
+            recipient: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::InsufficientFees {}) => {}
+            _ => panic!("Must return InsufficientFees error"),
+        }
+
+        // execute withdraw of everything to the treasury - should be success
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::WithdrawFees {
+            denom: Denom::Native("token".to_string()),
+            amount: None,
+            recipient: Some(Addr::unchecked("treasury")),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(10, "token"),
+            })]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetAccruedFees {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: FeesResponse = from_binary(&res).unwrap();
+        assert!(value.fees.is_empty());
+    }
+
+    #[test]
+    fn same_pair_concurrent_games() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start game twice against the same opponent - both succeed
+        for (expected_id, host_move) in [(1u64, GameMove::Rock), (2u64, GameMove::Paper)] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), host_move, "salt"),
+            };
+            let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let game_id: u64 = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(expected_id, game_id);
+            assert_eq!(res.attributes[1].key, "game_id");
+            assert_eq!(res.attributes[1].value, expected_id.to_string());
+        }
+
+        // respond to and reveal the second game only
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 2,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 2,
+            host_move: GameMove::Paper,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "Tie");
+
+        // the first game is untouched
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(1, value.id);
+        assert_eq!(GameStatus::AwaitingResponse, value.status);
+    }
+
+    #[test]
+    fn open_challenge() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start of two open challenges w/ different stakes
+        for amount in [5, 1] {
+            let info = mock_info("creator", &coins(amount, "token"));
+            let msg = ExecuteMsg::StartGame {
+                opponent: None,
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // query open challenges w/ and w/o a stake filter
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOpenChallenges {
+                stake: None,
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.games.len());
+
+        let five_token = Asset {
+            denom: Denom::Native("token".to_string()),
+            amount: Uint128::new(5),
+        };
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOpenChallenges {
+                stake: Some(five_token),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.games.len());
+        assert_eq!(1, value.games[0].id);
+        assert_eq!(None, value.games[0].opponent);
+
+        // execute the host accepting their own challenge - should error
+        let info = mock_info("creator", &coins(5, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::CannotPlaySelf {}) => {}
+            _ => panic!("Must return CannotPlaySelf error"),
+        }
+
+        // execute a blacklisted address accepting the challenge - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("black_listed"),
+            reason: None,
+            expires: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("black_listed", &coins(5, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::AcceptorAddressBlacklisted {}) => {}
+            _ => panic!("Must return AcceptorAddressBlacklisted error"),
+        }
+
+        // execute accept from an eligible address - should be success
+        let info = mock_info("first_player", &coins(5, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the game is no longer open and belongs to the acceptor
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOpenChallenges {
+                stake: None,
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.games.len());
+        assert_eq!(2, value.games[0].id);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByOpponent {
+                opponent: Addr::unchecked("first_player"),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.games.len());
+        assert_eq!(
+            Some(Addr::unchecked("first_player")),
+            value.games[0].opponent
+        );
+        assert_eq!(GameStatus::AwaitingReveal, value.games[0].status);
+
+        // a withdrawn challenge leaves the open challenge index
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 2,
+            reason: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let open = games()
+            .idx
+            .opponent_status
+            .prefix((String::new(), "awaiting_response".to_string()))
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(0, open);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOpenChallenges {
+                stake: None,
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert!(value.games.is_empty());
+    }
+
+    #[test]
+    fn cancel_and_decline() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // execute start of two games against the same opponent
+        for _ in 0..2 {
+            let info = mock_info("creator", &coins(2, "token"));
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // execute cancel from the opponent - should error
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 1,
+            reason: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // execute cancel from the host - stake is refunded
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 1,
+            reason: Some("changed my mind".to_string()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(2, "token"),
+            })]
+        );
+        assert_eq!(res.events[0].ty, "game_cancelled");
+        assert_eq!(res.events[0].attributes[2].key, "reason");
+        assert_eq!(res.events[0].attributes[2].value, "changed my mind");
+
+        // execute cancel of the same game again - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 1,
+            reason: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::GameEnded {}) => {}
+            _ => panic!("Must return GameEnded error"),
+        }
+
+        // execute decline from the host - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::DeclineGame {
+            game_id: 2,
+            reason: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // execute decline from the opponent - host is refunded
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::DeclineGame {
+            game_id: 2,
+            reason: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(2, "token"),
+            })]
+        );
+        assert_eq!(res.events[0].ty, "game_declined");
+        assert_eq!(res.events[0].attributes[2].value, "declined_by_opponent");
+
+        // a game that was already responded to cannot be cancelled
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &coins(2, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 3,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 3,
+            reason: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::AlreadyResponded {}) => {}
+            _ => panic!("Must return AlreadyResponded error"),
+        }
+    }
+
+    #[test]
+    fn best_of_match() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let host = Addr::unchecked("creator");
+
+        // execute start of a match w/ an even number of rounds - should error
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartMatch {
+            opponent: Addr::unchecked("first_player"),
+            best_of: 4,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::InvalidBestOf {}) => {}
+            _ => panic!("Must return InvalidBestOf error"),
+        }
+
+        // execute start of a best of 3 for 100 token - should be success
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartMatch {
+            opponent: Addr::unchecked("first_player"),
+            best_of: 3,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let match_id: u64 = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(1, match_id);
+
+        // first round is a tie, the opponent escrows the stake
+        let info = mock_info("first_player", &coins(100, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Rock,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: Some(hash_move(&host, GameMove::Scissors, "salt2")),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "next_game_id" && attr.value == "2"));
+
+        // host takes the next two rounds, later rounds carry no stake
+        for (game_id, host_move, opp_move, salt, next_commitment) in [
+            (
+                2,
+                GameMove::Scissors,
+                GameMove::Paper,
+                "salt2",
+                Some(hash_move(&host, GameMove::Rock, "salt3")),
+            ),
+            (3, GameMove::Rock, GameMove::Scissors, "salt3", None),
+        ] {
+            let info = mock_info("first_player", &[]);
+            let msg = ExecuteMsg::Respond { game_id, opp_move };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move,
+                salt: salt.to_string(),
+                next_commitment,
+            };
+            let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            if game_id == 3 {
+                assert_eq!(
+                    res.messages,
+                    vec![SubMsg::new(BankMsg::Send {
+                        to_address: "creator".to_string(),
+                        amount: coins(200, "token"),
+                    })]
+                );
+            }
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetMatch { match_id }).unwrap();
+        let value: Match = from_binary(&res).unwrap();
+        assert_eq!(MatchStatus::Finished, value.status);
+        assert_eq!(Some(GameResult::HostWins), value.result);
+        assert_eq!((2, 0), (value.host_score, value.opp_score));
+        assert_eq!(3, value.rounds.len());
+        assert_eq!(GameResult::Tie, value.rounds[0].result);
+        assert_eq!(None, value.current_game);
+
+        // execute reveal of an undecided match w/o the next commitment - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartMatch {
+            opponent: Addr::unchecked("first_player"),
+            best_of: 3,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 4,
+            opp_move: GameMove::Scissors,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 4,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::MissingNextCommitment {}) => {}
+            _ => panic!("Must return MissingNextCommitment error"),
+        }
+    }
+
+    #[test]
+    fn finished_game_history() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // play one game to the end and leave another pending
+        for _ in 0..2 {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height += 3;
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(GameStatus::Finished, value.status);
+        assert_eq!(Some(GameMove::Rock), value.host_move);
+        assert_eq!(Some(GameMove::Paper), value.opp_move);
+        assert_eq!(Some(GameResult::OpponentWins), value.result);
+        assert_eq!(Some(env.block.height), value.finished_height);
+        assert_eq!(Some(env.block.time), value.finished_time);
+
+        // both players see the finished game in their history only
+        for player in ["creator", "first_player"] {
+            let msg = QueryMsg::GetPlayerHistory {
+                player: Addr::unchecked(player),
+                start_after: None,
+                limit: None,
+                order: None,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: GamesListResponse = from_binary(&res).unwrap();
+            assert_eq!(1, value.games.len());
+            assert_eq!(1, value.games[0].id);
+        }
+
+        let msg = QueryMsg::GetGamesByHost {
+            address: Addr::unchecked("creator"),
+            start_after: None,
+            limit: None,
+            order: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.games.len());
+        assert_eq!(2, value.games[0].id);
+
+        // execute reveal of the finished game again - should error
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res {
+            Err(ContractError::GameEnded {}) => {}
+            _ => panic!("Must return GameEnded error"),
+        }
+    }
+
+    #[test]
+    fn paginated_lists() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for _ in 0..35 {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let by_host = |start_after, limit, order| QueryMsg::GetGamesByHost {
+            address: Addr::unchecked("creator"),
+            start_after,
+            limit,
+            order,
+        };
+
+        // default page size with a cursor to the next page
+        let res = query(deps.as_ref(), mock_env(), by_host(None, None, None)).unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(10, value.games.len());
+        assert_eq!(1, value.games[0].id);
+        assert_eq!(Some(10), value.next_start_after);
+
+        // oversized limits are capped
+        let res = query(deps.as_ref(), mock_env(), by_host(Some(3), Some(100), None)).unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(30, value.games.len());
+        assert_eq!(4, value.games[0].id);
+        assert_eq!(Some(33), value.next_start_after);
+
+        // the last page carries no cursor
+        let res = query(deps.as_ref(), mock_env(), by_host(Some(33), None, None)).unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![34, 35],
+            value.games.iter().map(|g| g.id).collect::<Vec<_>>()
+        );
+        assert_eq!(None, value.next_start_after);
+
+        // descending order walks back from the cursor
+        let msg = by_host(Some(5), Some(3), Some(OrderBy::Descending));
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: GamesListResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![4, 3, 2],
+            value.games.iter().map(|g| g.id).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(2), value.next_start_after);
+    }
+
+    #[test]
+    fn player_stats() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            fee_bps: Some(500),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // host wins a game for 100 token each, then a free game is tied
+        for (game_id, stake, opp_move) in [
+            (1, coins(100, "token"), GameMove::Scissors),
+            (2, vec![], GameMove::Rock),
+        ] {
+            let info = mock_info("creator", &stake);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("first_player", &stake);
+            let msg = ExecuteMsg::Respond { game_id, opp_move };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move: GameMove::Rock,
+                salt: "salt".to_string(),
+                next_commitment: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let token = |amount| Asset {
+            denom: Denom::Native("token".to_string()),
+            amount: Uint128::new(amount),
+        };
+
+        let msg = QueryMsg::GetPlayerStats {
+            address: Addr::unchecked("creator"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: PlayerStats = from_binary(&res).unwrap();
+        assert_eq!(
+            PlayerStats {
+                wins: 1,
+                losses: 0,
+                ties: 1,
+                games_played: 2,
+                wagered: vec![token(100)],
+                won: vec![token(190)],
+                last_played_height: mock_env().block.height,
+                rating: Some(1215),
+            },
+            value
+        );
+
+        // unknown addresses come back with empty stats
+        let msg = QueryMsg::GetPlayerStatsBatch {
+            addresses: vec![Addr::unchecked("first_player"), Addr::unchecked("nobody")],
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: PlayerStatsBatchResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.stats.len());
+        assert_eq!(Addr::unchecked("first_player"), value.stats[0].address);
+        assert_eq!(1, value.stats[0].stats.losses);
+        assert_eq!(vec![token(100)], value.stats[0].stats.wagered);
+        assert!(value.stats[0].stats.won.is_empty());
+        assert_eq!(PlayerStats::default(), value.stats[1].stats);
+    }
+
+    #[test]
+    fn leaderboard() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // creator beats first_player, loses to second_player and beats third_player
+        for (game_id, opponent, stake, opp_move) in [
+            (1, "first_player", coins(10, "token"), GameMove::Scissors),
+            (2, "second_player", coins(10, "token"), GameMove::Paper),
+            (3, "third_player", vec![], GameMove::Scissors),
+        ] {
+            let info = mock_info("creator", &stake);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked(opponent)),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info(opponent, &stake);
+            let msg = ExecuteMsg::Respond { game_id, opp_move };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move: GameMove::Rock,
+                salt: "salt".to_string(),
+                next_commitment: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let ranking = |value: &LeaderboardResponse| {
+            value
+                .entries
+                .iter()
+                .map(|entry| (entry.address.to_string(), entry.score))
+                .collect::<Vec<_>>()
+        };
+
+        let msg = QueryMsg::GetLeaderboard {
+            metric: LeaderboardMetric::Wins,
+            start_after: None,
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LeaderboardResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![("creator".to_string(), 2), ("second_player".to_string(), 1)],
+            ranking(&value)
+        );
+        assert_eq!(
+            Some(LeaderboardEntry {
+                address: Addr::unchecked("second_player"),
+                score: 1,
+            }),
+            value.next_start_after
+        );
+        let cursor = value.next_start_after;
+
+        let msg = QueryMsg::GetLeaderboard {
+            metric: LeaderboardMetric::Wins,
+            start_after: cursor.clone(),
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LeaderboardResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                ("third_player".to_string(), 0),
+                ("first_player".to_string(), 0)
+            ],
+            ranking(&value)
+        );
+
+        let msg = QueryMsg::GetLeaderboard {
+            metric: LeaderboardMetric::NetWinnings {
+                denom: Denom::Native("token".to_string()),
+            },
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LeaderboardResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                ("second_player".to_string(), 10),
+                ("creator".to_string(), 0),
+                ("first_player".to_string(), -10),
+            ],
+            ranking(&value)
+        );
+        assert_eq!(None, value.next_start_after);
+
+        // nobody has played enough games to be ranked by win rate
+        let msg = QueryMsg::GetLeaderboard {
+            metric: LeaderboardMetric::WinRate,
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LeaderboardResponse = from_binary(&res).unwrap();
+        assert!(value.entries.is_empty());
+
+        // the cursor holds its place after the cursor player's score changes
+        let info = mock_info("second_player", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("second_player"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 4,
+            opp_move: GameMove::Scissors,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("second_player", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 4,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = QueryMsg::GetLeaderboard {
+            metric: LeaderboardMetric::Wins,
+            start_after: cursor,
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LeaderboardResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                ("third_player".to_string(), 0),
+                ("first_player".to_string(), 0)
+            ],
+            ranking(&value)
+        );
+    }
+
+    #[test]
+    fn elo_ratings() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            default_rating: Some(1500),
+            k_factor: Some(20),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Scissors,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // evenly rated players move by half the k-factor
+        assert!(res.attributes.contains(&attr("host_rating", "1510")));
+        assert!(res.attributes.contains(&attr("host_rating_delta", "10")));
+        assert!(res.attributes.contains(&attr("opp_rating", "1490")));
+        assert!(res.attributes.contains(&attr("opp_rating_delta", "-10")));
+
+        let msg = QueryMsg::GetRating {
+            address: Addr::unchecked("first_player"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: u32 = from_binary(&res).unwrap();
+        assert_eq!(1490, value);
+
+        // players without games get the configured default
+        let msg = QueryMsg::GetRating {
+            address: Addr::unchecked("nobody"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: u32 = from_binary(&res).unwrap();
+        assert_eq!(1500, value);
+
+        let msg = QueryMsg::GetLeaderboard {
+            metric: LeaderboardMetric::Rating,
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LeaderboardResponse = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator"), value.entries[0].address);
+        assert_eq!(1510, value.entries[0].score);
+        assert_eq!(1490, value.entries[1].score);
+
+        // expected scores follow the table and stay complementary
+        assert_eq!(7597, expected_score_bps(1400, 1200));
+        assert_eq!(2403, expected_score_bps(1200, 1400));
+        assert_eq!(9901, expected_score_bps(3000, 1000));
+        assert_eq!(
+            10_000,
+            expected_score_bps(1210, 1200) + expected_score_bps(1200, 1210)
+        );
+    }
+
+    #[test]
+    fn head_to_head() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_record = |deps: Deps| {
+            let msg = QueryMsg::GetHeadToHead {
+                a: Addr::unchecked("first_player"),
+                b: Addr::unchecked("creator"),
+            };
+            let res = query(deps, mock_env(), msg).unwrap();
+            from_binary::<HeadToHeadResponse>(&res).unwrap()
+        };
+
+        // first_player wins once, then creator wins twice in a row and they tie
+        for (game_id, opp_move) in [
+            (1, GameMove::Paper),
+            (2, GameMove::Scissors),
+            (3, GameMove::Scissors),
+            (4, GameMove::Rock),
+        ] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("first_player", &[]);
+            let msg = ExecuteMsg::Respond { game_id, opp_move };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move: GameMove::Rock,
+                salt: "salt".to_string(),
+                next_commitment: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            if game_id == 3 {
+                let value = query_record(deps.as_ref());
+                assert_eq!(Some(Addr::unchecked("creator")), value.streak_holder);
+                assert_eq!(2, value.streak);
+            }
+        }
+
+        let value = query_record(deps.as_ref());
+        assert_eq!((1, 2, 1), (value.a_wins, value.b_wins, value.ties));
+        assert_eq!(None, value.streak_holder);
+        assert_eq!(0, value.streak);
+        assert_eq!(
+            vec![
+                HeadToHeadGame {
+                    game_id: 4,
+                    winner: None,
+                },
+                HeadToHeadGame {
+                    game_id: 3,
+                    winner: Some(Addr::unchecked("creator")),
+                },
+                HeadToHeadGame {
+                    game_id: 2,
+                    winner: Some(Addr::unchecked("creator")),
+                },
+                HeadToHeadGame {
+                    game_id: 1,
+                    winner: Some(Addr::unchecked("first_player")),
+                },
+            ],
+            value.recent
+        );
+    }
+
+    #[test]
+    fn list_games_by_status() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            response_timeout: Some(Duration::Height(10)),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // games 1-3 against first_player, game 4 between other players
+        for (host, opponent) in [
+            ("creator", "first_player"),
+            ("creator", "first_player"),
+            ("creator", "first_player"),
+            ("second_player", "third_player"),
+        ] {
+            let info = mock_info(host, &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked(opponent)),
+                host_commitment: hash_move(&Addr::unchecked(host), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // game 1 is cancelled, game 2 awaits the reveal and game 3 expires
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 1,
+            reason: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 2,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height += 10;
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 3 };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let list = |deps: Deps, status: Option<GameStatus>, player: Option<&str>| {
+            let msg = QueryMsg::ListGames {
+                status,
+                player: player.map(Addr::unchecked),
+                start_after: None,
+                limit: None,
+                order: None,
+            };
+            let res = query(deps, mock_env(), msg).unwrap();
+            let value: GamesListResponse = from_binary(&res).unwrap();
+            value.games.iter().map(|g| g.id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![1],
+            list(deps.as_ref(), Some(GameStatus::Cancelled), None)
+        );
+        assert_eq!(
+            vec![3],
+            list(deps.as_ref(), Some(GameStatus::Expired), None)
+        );
+        assert_eq!(
+            vec![4],
+            list(deps.as_ref(), Some(GameStatus::AwaitingResponse), None)
+        );
+        assert_eq!(
+            vec![2],
+            list(
+                deps.as_ref(),
+                Some(GameStatus::AwaitingReveal),
+                Some("first_player")
+            )
+        );
+        assert_eq!(
+            vec![1, 2, 3],
+            list(deps.as_ref(), None, Some("first_player"))
+        );
+        assert_eq!(vec![1, 2, 3, 4], list(deps.as_ref(), None, None));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 3 }).unwrap();
+        let value: Game = from_binary(&res).unwrap();
+        assert_eq!(GameStatus::Expired, value.status);
+        assert_eq!(None, value.result);
+    }
+
+    #[test]
+    fn global_stats() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            fee_bps: Some(500),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // a won game for 100 token each, a free tie and a cancelled game
+        for (game_id, stake, opp_move) in [
+            (1, coins(100, "token"), GameMove::Scissors),
+            (2, vec![], GameMove::Rock),
+        ] {
+            let info = mock_info("creator", &stake);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("first_player", &stake);
+            let msg = ExecuteMsg::Respond { game_id, opp_move };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move: GameMove::Rock,
+                salt: "salt".to_string(),
+                next_commitment: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("second_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 3,
+            reason: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGlobalStats {}).unwrap();
+        let value: GlobalStats = from_binary(&res).unwrap();
+        let token = |amount| Asset {
+            denom: Denom::Native("token".to_string()),
+            amount: Uint128::new(amount),
+        };
+        assert_eq!(
+            GlobalStats {
+                games_started: 3,
+                games_resolved: 2,
+                games_cancelled: 1,
+                games_expired: 0,
+                ties: 1,
+                distinct_players: 2,
+                volume: vec![token(200)],
+                fees_collected: vec![token(10)],
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn move_stats() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // host plays rock twice, winning against scissors and losing to paper
+        for (game_id, opp_move) in [(1, GameMove::Scissors), (2, GameMove::Paper)] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked("first_player")),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("first_player", &[]);
+            let msg = ExecuteMsg::Respond { game_id, opp_move };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move: GameMove::Rock,
+                salt: "salt".to_string(),
+                next_commitment: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let counts = |rock, paper, scissors| MoveCounts {
+            rock,
+            paper,
+            scissors,
+        };
+
+        let msg = QueryMsg::GetMoveStats { address: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: MoveStats = from_binary(&res).unwrap();
+        assert_eq!(
+            MoveStats {
+                as_host: counts(2, 0, 0),
+                as_opponent: counts(0, 1, 1),
+                wins: counts(1, 1, 0),
+            },
+            value
+        );
+
+        let msg = QueryMsg::GetMoveStats {
+            address: Some(Addr::unchecked("first_player")),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: MoveStats = from_binary(&res).unwrap();
+        assert_eq!(
+            MoveStats {
+                as_host: counts(0, 0, 0),
+                as_opponent: counts(0, 1, 1),
+                wins: counts(0, 1, 0),
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn daily_activity() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let today = mock_env().block.time.seconds() / SECONDS_PER_DAY;
+
+        // two games today, one of them for 10 token each, and one tomorrow
+        for (game_id, opponent, stake, days_later) in [
+            (1, "first_player", coins(10, "token"), 0),
+            (2, "second_player", vec![], 0),
+            (3, "first_player", vec![], 1),
+        ] {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(days_later * SECONDS_PER_DAY);
+
+            let info = mock_info("creator", &stake);
+            let msg = ExecuteMsg::StartGame {
+                opponent: Some(Addr::unchecked(opponent)),
+                host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+            let info = mock_info(opponent, &stake);
+            let msg = ExecuteMsg::Respond {
+                game_id,
+                opp_move: GameMove::Scissors,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Reveal {
+                game_id,
+                host_move: GameMove::Rock,
+                salt: "salt".to_string(),
+                next_commitment: None,
+            };
+            let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::GetActivity {
+            from: today - 1,
+            to: today + 1,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ActivityResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                DailyActivity {
+                    day: today,
+                    games_played: 2,
+                    unique_players: 3,
+                    volume: vec![Asset {
+                        denom: Denom::Native("token".to_string()),
+                        amount: Uint128::new(20),
+                    }],
+                },
+                DailyActivity {
+                    day: today + 1,
+                    games_played: 1,
+                    unique_players: 2,
+                    volume: vec![],
+                },
+            ],
+            value.days
+        );
+
+        // ranges wider than the maximum are rejected
+        let msg = QueryMsg::GetActivity {
+            from: today,
+            to: today + MAX_ACTIVITY_DAYS,
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn blacklist_entries() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        let env = mock_env();
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("cheater"),
+            reason: Some(String::from("colluding")),
+            expires: Some(Expiration::AtHeight(env.block.height + 10)),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // re-adding replaces the entry instead of failing
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("cheater"),
+            reason: Some(String::from("colluding again")),
+            expires: Some(Expiration::AtHeight(env.block.height + 10)),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("spammer"),
+            reason: None,
+            expires: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListBlacklist {
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: BlacklistResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.entries.len());
+        assert_eq!(Addr::unchecked("cheater"), value.entries[0].address);
+        assert_eq!(
+            Some(String::from("colluding again")),
+            value.entries[0].entry.reason
+        );
+        assert_eq!(Addr::unchecked("creator"), value.entries[0].entry.added_by);
+        assert_eq!(Some(Addr::unchecked("cheater")), value.next_start_after);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListBlacklist {
+                start_after: value.next_start_after,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: BlacklistResponse = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("spammer"), value.entries[0].address);
+
+        let start_game = |host: &str| ExecuteMsg::StartGame {
+            opponent: None,
+            host_commitment: hash_move(&Addr::unchecked(host), GameMove::Rock, "salt"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cheater", &[]),
+            start_game("cheater"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::HostAddressBlacklisted {}));
+
+        // the ban lapses once its expiry is reached
+        let mut later = env.clone();
+        later.block.height += 10;
+        let _res = execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info("cheater", &[]),
+            start_game("cheater"),
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: Addr::unchecked("cheater"),
+            reason: None,
+            expires: Some(Expiration::AtHeight(later.block.height)),
+        };
+        let err =
+            execute(deps.as_mut(), later.clone(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExpiration {}));
+
+        let msg = ExecuteMsg::RemoveFromBlacklist {
+            address: Addr::unchecked("spammer"),
+        };
+        let _res = execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), later, mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotBlacklisted {}));
+    }
+
+    #[test]
+    fn banned_player_games_cancelled() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        let host = Addr::unchecked("creator");
+        let cheater = Addr::unchecked("cheater");
+
+        // a challenge to the cheater nobody responded to yet
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(cheater.clone()),
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // a game the cheater hosts, waiting on their reveal
+        let info = mock_info("cheater", &coins(50, "token"));
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&cheater, GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &coins(50, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 2,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // a match against the cheater that is under way
+        let info = mock_info("creator", &coins(30, "token"));
+        let msg = ExecuteMsg::StartMatch {
+            opponent: cheater.clone(),
+            best_of: 3,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("cheater", &coins(30, "token"));
+        let msg = ExecuteMsg::Respond {
+            game_id: 3,
+            opp_move: GameMove::Rock,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 3,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: Some(hash_move(&host, GameMove::Paper, "salt2")),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query games by host and opponent addresses - fail
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::GetGame {
-                host: Addr::unchecked("creator"),
-                opponent: Addr::unchecked("not_a_real_player"),
-            },
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddToBlacklist {
+            address: cheater.clone(),
+            reason: None,
+            expires: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        // the banned player's games are cancelled in batches
+        let refund = |to: &str, amount| {
+            SubMsg::new(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: coins(amount, "token"),
+            })
+        };
+        let remaining = |res: &Response| {
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "games_remaining")
+                .map(|attr| attr.value.clone())
+        };
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelBannedGames {
+            address: cheater.clone(),
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            vec![
+                refund("creator", 100),
+                refund("cheater", 50),
+                refund("first_player", 50),
+            ],
+            res.messages
         );
+        assert_eq!(2, res.events.len());
+        assert_eq!(Some("true".to_string()), remaining(&res));
 
-        match res {
-            Err(_std_error) => {}
-            _ => panic!("Must return Game not found error"),
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelBannedGames {
+            address: cheater.clone(),
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            vec![refund("creator", 30), refund("cheater", 30)],
+            res.messages
+        );
+        assert_eq!(Some("false".to_string()), remaining(&res));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelBannedGames {
+            address: Addr::unchecked("first_player"),
+            limit: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotBlacklisted {}));
+
+        for game_id in [1, 2, 4] {
+            let game = games().load(deps.as_ref().storage, game_id).unwrap();
+            assert_eq!(GameStatus::Cancelled, game.status);
+        }
+        let game_match = MATCHES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(MatchStatus::Cancelled, game_match.status);
+        assert_eq!(None, game_match.current_game);
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(cheater.clone()),
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::OpponentAddressBlacklisted {}));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartMatch {
+            opponent: cheater,
+            best_of: 3,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::OpponentAddressBlacklisted {}));
+
+        let info = mock_info("cheater", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Rock,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ResponderAddressBlacklisted {}));
+    }
+
+    #[test]
+    fn invite_only() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            invite_only: Some(true),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let start_game = |host: &str| ExecuteMsg::StartGame {
+            opponent: None,
+            host_commitment: hash_move(&Addr::unchecked(host), GameMove::Rock, "salt"),
+        };
+
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, start_game("creator")).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowlisted {}));
+
+        // only the admin manages the allowlist
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::AddToAllowlist {
+            address: Addr::unchecked("first_player"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Admin(_)));
+
+        for address in ["creator", "first_player"] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::AddToAllowlist {
+                address: Addr::unchecked(address),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         }
 
-        // query games by host and opponent addresses - success
         let res = query(
             deps.as_ref(),
             mock_env(),
-            QueryMsg::GetGame {
-                host: Addr::unchecked("creator"),
-                opponent: Addr::unchecked("other_player"),
+            QueryMsg::ListAllowlist {
+                start_after: None,
+                limit: None,
             },
         )
         .unwrap();
+        let value: AllowlistResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Addr::unchecked("creator"), Addr::unchecked("first_player")],
+            value.addresses
+        );
 
-        let value: Game = from_binary(&res).unwrap();
-
-        assert_eq!(Addr::unchecked("creator"), value.host);
-        assert_eq!(Addr::unchecked("other_player"), value.opponent);
-        assert_eq!(GameMove::Rock, value.host_move);
-        assert_eq!(None, value.opp_move);
-        assert_eq!(None, value.result);
-    }
+        // a named opponent must be allowlisted too
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("second_player")),
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::OpponentNotAllowlisted {}));
 
-    #[test]
-    fn get_admin() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator_man", &coins(1000, "earth"));
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::StartMatch {
+            opponent: Addr::unchecked("second_player"),
+            best_of: 3,
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::OpponentNotAllowlisted {}));
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, start_game("creator")).unwrap();
 
-        // query admin success
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
-        let value: Addr = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("creator_man"), value);
-    }
+        // an open challenge can only be taken by an allowlisted address
+        let info = mock_info("second_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowlisted {}));
 
-    #[test]
-    fn update_admin() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator_man", &coins(1000, "earth"));
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Respond {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RemoveFromAllowlist {
+            address: Addr::unchecked("first_player"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query 1st admin success
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
-        let value: Addr = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("creator_man"), value);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IsAllowlisted {
+                address: Addr::unchecked("first_player"),
+            },
+        )
+        .unwrap();
+        let value: bool = from_binary(&res).unwrap();
+        assert!(!value);
 
-        // execute admin update
-        let info = mock_info("creator_man", &coins(2, "token"));
-        let msg = ExecuteMsg::UpdateAdmin {
-            admin: Addr::unchecked("updated_man"),
-        };
+        // once invite-only mode is off anyone can play again
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetInviteOnly { enabled: false };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query 2nd admin success
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
-        let value: Addr = from_binary(&res).unwrap();
-        assert_eq!(Addr::unchecked("updated_man"), value);
+        let info = mock_info("second_player", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, start_game("second_player")).unwrap();
     }
 
     #[test]
-    fn host_blacklist() {
+    fn pause_and_unpause() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let host = Addr::unchecked("creator");
+        let start_game = || ExecuteMsg::StartGame {
+            opponent: Some(Addr::unchecked("first_player")),
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
+        };
+        let respond = |game_id| ExecuteMsg::Respond {
+            game_id,
+            opp_move: GameMove::Paper,
+        };
 
-        // execute add to blacklist
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::AddToBlacklist {
-            address: Addr::unchecked("host_black_listed"),
+        for _ in 0..2 {
+            let info = mock_info("creator", &coins(10, "token"));
+            let _res = execute(deps.as_mut(), mock_env(), info, start_game()).unwrap();
+        }
+
+        let info = mock_info("first_player", &[]);
+        let msg = ExecuteMsg::Pause {
+            scope: PauseScope::All,
+            reason: None,
         };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Admin(_)));
 
-        // execute start game w/ opponent and host move
-        let info = mock_info("host_black_listed", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
-            host_move: GameMove::Rock,
+        // freezing new games still lets started games be played
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Pause {
+            scope: PauseScope::NewGames,
+            reason: Some("upgrade".to_string()),
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        match res {
-            Err(ContractError::HostAddressBlacklisted {}) => {}
-            _ => panic!("Must return BlackListedAddress error"),
-        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPauseStatus {}).unwrap();
+        let value: Option<PauseInfo> = from_binary(&res).unwrap();
+        let value = value.unwrap();
+        assert_eq!(PauseScope::NewGames, value.scope);
+        assert_eq!(Some("upgrade".to_string()), value.reason);
 
-        // execute remove from blacklist
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::RemoveFromBlacklist {
-            address: Addr::unchecked("host_black_listed"),
+        let info = mock_info("creator", &coins(10, "token"));
+        let err = execute(deps.as_mut(), mock_env(), info, start_game()).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+
+        let info = mock_info("first_player", &coins(10, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, respond(1)).unwrap();
+
+        // a full pause blocks responses too, but the host can still reveal and cancel
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Pause {
+            scope: PauseScope::All,
+            reason: None,
         };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute start game w/ opponent and host move
-        let info = mock_info("host_black_listed", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
-            opponent: Addr::unchecked("other_player"),
+        let info = mock_info("first_player", &coins(10, "token"));
+        let err = execute(deps.as_mut(), mock_env(), info, respond(2)).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
             host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: None,
         };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query games by host address
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::GetGamesByHost {
-                address: Addr::unchecked("host_black_listed"),
-            },
-        )
-        .unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 2,
+            reason: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
 
-        let value: GamesListResponse = from_binary(&res).unwrap();
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unpause {}).unwrap();
 
-        assert_eq!(1, value.games.len());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPauseStatus {}).unwrap();
+        let value: Option<PauseInfo> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
 
-        assert_eq!(Addr::unchecked("host_black_listed"), value.games[0].host);
-        assert_eq!(Addr::unchecked("other_player"), value.games[0].opponent);
-        assert_eq!(GameMove::Rock, value.games[0].host_move);
-        assert_eq!(None, value.games[0].opp_move);
-        assert_eq!(None, value.games[0].result);
+        let info = mock_info("creator", &coins(10, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, start_game()).unwrap();
     }
 
     #[test]
-    fn full_game_tie() {
+    fn barred_opponent_does_not_forfeit() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = InstantiateMsg {
+            response_timeout: Some(Duration::Height(5)),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute start game w/ 1st opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
+        let host = Addr::unchecked("creator");
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartMatch {
             opponent: Addr::unchecked("first_player"),
-            host_move: GameMove::Rock,
+            best_of: 3,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute try response from non-opponent - should end with error
-        let info = mock_info("second_player", &coins(2, "token"));
+        let info = mock_info("first_player", &coins(100, "token"));
         let msg = ExecuteMsg::Respond {
-            host: Addr::unchecked("creator"),
-            opponent: Addr::unchecked("first_player"),
-            opp_move: GameMove::Rock,
+            game_id: 1,
+            opp_move: GameMove::Paper,
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // should error
-        match res {
-            Err(ContractError::Unauthorized {}) => {}
-            _ => panic!("Must return Unauthorized error"),
-        }
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: Some(hash_move(&host, GameMove::Rock, "salt2")),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute try response from opponent - should be success
-        let info = mock_info("first_player", &coins(2, "token"));
+        // invite-only mode shuts the opponent out of the next round
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddToAllowlist {
+            address: host.clone(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetInviteOnly { enabled: true };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("first_player", &[]);
         let msg = ExecuteMsg::Respond {
-            host: Addr::unchecked("creator"),
-            opponent: Addr::unchecked("first_player"),
-            opp_move: GameMove::Rock,
+            game_id: 2,
+            opp_move: GameMove::Paper,
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowlisted {}));
 
-        println!("{:?}", res);
+        let mut env = mock_env();
+        env.block.height += 6;
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 2 };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
 
-        assert_eq!(res.attributes[0].key, "method");
-        assert_eq!(res.attributes[0].value, "response");
-        assert_eq!(res.attributes[1].key, "result");
-        assert_eq!(res.attributes[1].value, "Tie");
+        let refund = |to: &str| {
+            SubMsg::new(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: coins(100, "token"),
+            })
+        };
+        assert_eq!(
+            vec![refund("creator"), refund("first_player")],
+            res.messages
+        );
+
+        let game_match = MATCHES.load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(MatchStatus::Cancelled, game_match.status);
+        assert_eq!(None, game_match.result);
+        let game = games().load(deps.as_ref().storage, 2).unwrap();
+        assert_eq!(GameStatus::Cancelled, game.status);
     }
 
     #[test]
-    fn full_game_host_wins() {
+    fn no_forfeit_while_paused() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = InstantiateMsg {
+            response_timeout: Some(Duration::Height(5)),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute start game w/ 1st opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
+        // the opponent takes the first round of a best of 3 for 100 token each
+        let host = Addr::unchecked("creator");
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartMatch {
             opponent: Addr::unchecked("first_player"),
-            host_move: GameMove::Rock,
+            best_of: 3,
+            host_commitment: hash_move(&host, GameMove::Rock, "salt"),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute try response from opponent - should be success
-        let info = mock_info("first_player", &coins(2, "token"));
+        let info = mock_info("first_player", &coins(100, "token"));
         let msg = ExecuteMsg::Respond {
-            host: Addr::unchecked("creator"),
-            opponent: Addr::unchecked("first_player"),
-            opp_move: GameMove::Scissors,
+            game_id: 1,
+            opp_move: GameMove::Paper,
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        println!("{:?}", res);
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reveal {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: "salt".to_string(),
+            next_commitment: Some(hash_move(&host, GameMove::Rock, "salt2")),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        assert_eq!(res.attributes[0].key, "method");
-        assert_eq!(res.attributes[0].value, "response");
-        assert_eq!(res.attributes[1].key, "result");
-        assert_eq!(res.attributes[1].value, "Host Won");
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Pause {
+            scope: PauseScope::All,
+            reason: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let respond = ExecuteMsg::Respond {
+            game_id: 2,
+            opp_move: GameMove::Paper,
+        };
+        let info = mock_info("first_player", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, respond.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+
+        // the round's deadline passes during the pause
+        let mut env = mock_env();
+        env.block.height += 6;
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 2 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+
+        // after unpausing the opponent gets a full response window back
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unpause {}).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 2 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DeadlineNotReached {}));
+
+        let info = mock_info("first_player", &[]);
+        let _res = execute(deps.as_mut(), env, info, respond).unwrap();
+        let game = games().load(deps.as_ref().storage, 2).unwrap();
+        assert_eq!(GameStatus::AwaitingReveal, game.status);
     }
 
     #[test]
-    fn full_game_opp_wins() {
+    fn commitment_must_be_sha256() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
 
-        // execute start game w/ 1st opponent and host move
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::StartGame {
+        for host_commitment in [Binary::default(), Binary::from(b"rock".to_vec())] {
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartGame {
+                opponent: None,
+                host_commitment: host_commitment.clone(),
+            };
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidCommitment {}));
+
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::StartMatch {
+                opponent: Addr::unchecked("first_player"),
+                best_of: 3,
+                host_commitment,
+            };
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidCommitment {}));
+        }
+    }
+
+    #[test]
+    fn withdrawn_match_kept() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        let info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::StartMatch {
             opponent: Addr::unchecked("first_player"),
-            host_move: GameMove::Rock,
+            best_of: 3,
+            host_commitment: hash_move(&Addr::unchecked("creator"), GameMove::Rock, "salt"),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // execute try response from opponent - should be success
-        let info = mock_info("first_player", &coins(2, "token"));
-        let msg = ExecuteMsg::Respond {
-            host: Addr::unchecked("creator"),
-            opponent: Addr::unchecked("first_player"),
-            opp_move: GameMove::Paper,
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelGame {
+            game_id: 1,
+            reason: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
 
-        println!("{:?}", res);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+        let game: Game = from_binary(&res).unwrap();
+        assert_eq!(Some(1), game.match_id);
 
-        assert_eq!(res.attributes[0].key, "method");
-        assert_eq!(res.attributes[0].value, "response");
-        assert_eq!(res.attributes[1].key, "result");
-        assert_eq!(res.attributes[1].value, "Opponent Won");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetMatch { match_id: 1 },
+        )
+        .unwrap();
+        let game_match: Match = from_binary(&res).unwrap();
+        assert_eq!(MatchStatus::Cancelled, game_match.status);
+        assert_eq!(None, game_match.current_game);
+        assert_eq!(None, game_match.result);
     }
 }
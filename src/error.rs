@@ -1,5 +1,5 @@
 use cosmwasm_std::StdError;
-use cw_controllers::{AdminError, HookError};
+use cw_controllers::AdminError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,20 +10,107 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Cannot migrate from a different contract: {name}")]
+    WrongContract { name: String },
+
+    #[error("Cannot migrate from version {stored} to the older {current}")]
+    CannotDowngrade { stored: String, current: String },
+
+    #[error("Invalid contract version: {0}")]
+    Version(String),
+
     #[error("No admin found")]
     Admin(#[from] AdminError),
 
-    #[error("{0}")]
-    Hook(#[from] HookError),
-
     #[error("The host address is blacklisted")]
     HostAddressBlacklisted {},
 
-    #[error("Only one game can be played with the same opponent at one time")]
-    OnlyOneGameAtATime {},
+    #[error("The address accepting the challenge is blacklisted")]
+    AcceptorAddressBlacklisted {},
+
+    #[error("The opponent address is blacklisted")]
+    OpponentAddressBlacklisted {},
+
+    #[error("The responding address is blacklisted")]
+    ResponderAddressBlacklisted {},
+
+    #[error("Play is paused")]
+    Paused {},
 
-    #[error("No game found between the host and the opponent")]
+    #[error("The contract is invite-only and the address is not on the allowlist")]
+    NotAllowlisted {},
+
+    #[error("The contract is invite-only and the opponent is not on the allowlist")]
+    OpponentNotAllowlisted {},
+
+    #[error("The address is not blacklisted")]
+    NotBlacklisted {},
+
+    #[error("The blacklist entry would already be expired")]
+    InvalidExpiration {},
+
+    #[error("The host cannot play against themselves")]
+    CannotPlaySelf {},
+
+    #[error("No game found with this id")]
     NoGameFound {},
+
+    #[error("The opponent has already responded to this game")]
+    AlreadyResponded {},
+
+    #[error("The opponent has not responded to this game yet")]
+    AwaitingResponse {},
+
+    #[error("This game has already ended")]
+    GameEnded {},
+
+    #[error("The revealed move does not match the host's commitment")]
+    InvalidReveal {},
+
+    #[error("The commitment must be a 32 byte sha256 digest")]
+    InvalidCommitment {},
+
+    #[error("The deadline for this game has not passed yet")]
+    DeadlineNotReached {},
+
+    #[error("This message does not take funds")]
+    UnexpectedFunds {},
+
+    #[error("A wager must be a single non-zero coin")]
+    InvalidStake {},
+
+    #[error("This game requires a wager of {expected}")]
+    MissingStake { expected: String },
+
+    #[error("Sent funds {received} do not match the wager of {expected}")]
+    StakeMismatch { expected: String, received: String },
+
+    #[error("The cw20 contract is not allowed for wagers")]
+    Cw20NotAllowed {},
+
+    #[error("The fee can be at most 10000 basis points")]
+    InvalidFee {},
+
+    #[error("Not enough fees accrued for this withdrawal")]
+    InsufficientFees {},
+
+    #[error("A match must be played over an odd number of rounds between 3 and 9")]
+    InvalidBestOf {},
+
+    #[error("No match found with this id")]
+    NoMatchFound {},
+
+    #[error("The match continues, a commitment for the next round is required")]
+    MissingNextCommitment {},
+
+    #[error("The match is already under way and can no longer be withdrawn")]
+    MatchInProgress {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        ContractError::Version(err.to_string())
+    }
+}
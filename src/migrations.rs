@@ -0,0 +1,261 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Env, Order, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+use crate::contract::{hash_move, DEFAULT_K_FACTOR, DEFAULT_RATING, DEFAULT_TIMEOUT};
+use crate::state::{
+    games, BlacklistEntry, Game, GameMove, GameResult, GameStatus, State, BLACKLIST, GAME_COUNT,
+    STATE,
+};
+
+/// Config as stored by v0.1.0, which only knew the owner.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyState {
+    pub owner: Addr,
+}
+
+pub const LEGACY_STATE: Item<LegacyState> = Item::new("state");
+
+/// Rewrites the v0.1.0 config into the current `State`, filling every setting it
+/// lacked with the defaults `instantiate` uses.
+pub fn migrate_legacy_state(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = LEGACY_STATE.load(storage)?;
+
+    let state = State {
+        owner: legacy.owner,
+        response_timeout: DEFAULT_TIMEOUT,
+        reveal_timeout: DEFAULT_TIMEOUT,
+        fee_bps: 0,
+        default_rating: DEFAULT_RATING,
+        k_factor: DEFAULT_K_FACTOR,
+        invite_only: false,
+    };
+    STATE.save(storage, &state)
+}
+
+/// Game as stored by v0.1.0, keyed by the (host, opponent) pair.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyGame {
+    pub host: Addr,
+    pub opponent: Addr,
+    pub host_move: GameMove,
+    pub opp_move: Option<GameMove>,
+    pub result: Option<GameResult>,
+}
+
+pub const LEGACY_GAME: Map<(&Addr, &Addr), LegacyGame> = Map::new("game");
+
+/// Moves pair-keyed games into `games()` under fresh ids and returns how many were
+/// moved. Legacy games stored the host move in plain text, so it is committed with
+/// an empty salt, which the host has to use when revealing. Deadlines restart from
+/// the current block using the timeouts in `STATE`.
+pub fn migrate_pair_keyed_games(storage: &mut dyn Storage, env: &Env) -> StdResult<u64> {
+    let state = STATE.load(storage)?;
+
+    let legacy_games: StdResult<Vec<_>> = LEGACY_GAME
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    let legacy_games = legacy_games?;
+
+    let mut game_id = GAME_COUNT.may_load(storage)?.unwrap_or_default();
+
+    for ((host, opponent), legacy) in legacy_games.iter() {
+        game_id += 1;
+
+        let (status, expires) = match legacy.opp_move {
+            Some(_) => (
+                GameStatus::AwaitingReveal,
+                state.reveal_timeout.after(&env.block),
+            ),
+            None => (
+                GameStatus::AwaitingResponse,
+                state.response_timeout.after(&env.block),
+            ),
+        };
+
+        let game = Game {
+            id: game_id,
+            host: legacy.host.clone(),
+            opponent: Some(legacy.opponent.clone()),
+            host_commitment: hash_move(&legacy.host, legacy.host_move, ""),
+            host_move: None,
+            opp_move: legacy.opp_move,
+            status,
+            result: None,
+            stake: None,
+            created_height: env.block.height,
+            created_time: env.block.time,
+            expires,
+            match_id: None,
+            finished_height: None,
+            finished_time: None,
+        };
+
+        games().save(storage, game_id, &game)?;
+        LEGACY_GAME.remove(storage, (host, opponent));
+    }
+
+    GAME_COUNT.save(storage, &game_id)?;
+
+    Ok(legacy_games.len() as u64)
+}
+
+/// Blacklist as stored by v0.1.0, which kept it in a `cw_controllers::Hooks` list.
+pub const LEGACY_BLACKLIST: Item<Vec<Addr>> = Item::new("cw4-hooks");
+
+/// Moves addresses from the legacy hooks list into `BLACKLIST` and returns how many
+/// were moved. The legacy list recorded neither who added an address nor why, so
+/// entries are attributed to the contract itself, at the current block time.
+pub fn migrate_hooks_blacklist(storage: &mut dyn Storage, env: &Env) -> StdResult<u64> {
+    let addresses = LEGACY_BLACKLIST.may_load(storage)?.unwrap_or_default();
+
+    for address in addresses.iter() {
+        let entry = BlacklistEntry {
+            reason: None,
+            added_by: env.contract.address.clone(),
+            added_at: env.block.time,
+            expires: None,
+        };
+        BLACKLIST.save(storage, address, &entry)?;
+    }
+    LEGACY_BLACKLIST.remove(storage);
+
+    Ok(addresses.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{instantiate, migrate};
+    use crate::msg::{InstantiateMsg, MigrateMsg};
+    use crate::ContractError;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cw2::{get_contract_version, set_contract_version};
+
+    #[test]
+    fn pair_keyed_games_get_ids() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        let host = Addr::unchecked("creator");
+        for (opponent, host_move) in [
+            ("first_player", GameMove::Rock),
+            ("second_player", GameMove::Paper),
+        ] {
+            let opponent = Addr::unchecked(opponent);
+            let legacy = LegacyGame {
+                host: host.clone(),
+                opponent: opponent.clone(),
+                host_move,
+                opp_move: None,
+                result: None,
+            };
+            LEGACY_GAME
+                .save(deps.as_mut().storage, (&host, &opponent), &legacy)
+                .unwrap();
+        }
+
+        let migrated = migrate_pair_keyed_games(deps.as_mut().storage, &mock_env()).unwrap();
+        assert_eq!(2, migrated);
+
+        assert_eq!(2, GAME_COUNT.load(deps.as_ref().storage).unwrap());
+        let remaining = LEGACY_GAME
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(0, remaining);
+
+        let game = games().load(deps.as_ref().storage, 2).unwrap();
+        assert_eq!(Some(Addr::unchecked("second_player")), game.opponent);
+        assert_eq!(GameStatus::AwaitingResponse, game.status);
+        assert_eq!(hash_move(&host, GameMove::Paper, ""), game.host_commitment);
+    }
+
+    #[test]
+    fn hooks_blacklist_moves_over() {
+        let mut deps = mock_dependencies();
+        let addresses = vec![Addr::unchecked("cheater"), Addr::unchecked("spammer")];
+        LEGACY_BLACKLIST
+            .save(deps.as_mut().storage, &addresses)
+            .unwrap();
+
+        let migrated = migrate_hooks_blacklist(deps.as_mut().storage, &mock_env()).unwrap();
+        assert_eq!(2, migrated);
+
+        assert_eq!(
+            None,
+            LEGACY_BLACKLIST.may_load(deps.as_ref().storage).unwrap()
+        );
+        let entry = BLACKLIST
+            .load(deps.as_ref().storage, &Addr::unchecked("spammer"))
+            .unwrap();
+        assert_eq!(mock_env().contract.address, entry.added_by);
+        assert_eq!(None, entry.expires);
+    }
+
+    #[test]
+    fn migrate_from_v0_1_0() {
+        let mut deps = mock_dependencies();
+        let owner = Addr::unchecked("creator");
+        let opponent = Addr::unchecked("first_player");
+
+        set_contract_version(deps.as_mut().storage, "crates.io:rps-dapp", "0.1.0").unwrap();
+        LEGACY_STATE
+            .save(
+                deps.as_mut().storage,
+                &LegacyState {
+                    owner: owner.clone(),
+                },
+            )
+            .unwrap();
+        let legacy = LegacyGame {
+            host: owner.clone(),
+            opponent: opponent.clone(),
+            host_move: GameMove::Rock,
+            opp_move: Some(GameMove::Paper),
+            result: None,
+        };
+        LEGACY_GAME
+            .save(deps.as_mut().storage, (&owner, &opponent), &legacy)
+            .unwrap();
+        LEGACY_BLACKLIST
+            .save(deps.as_mut().storage, &vec![Addr::unchecked("cheater")])
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "games_migrated" && attr.value == "1"));
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(owner, state.owner);
+        assert!(!state.invite_only);
+        let game = games().load(deps.as_ref().storage, 1).unwrap();
+        assert_eq!(GameStatus::AwaitingReveal, game.status);
+        assert!(BLACKLIST.has(deps.as_ref().storage, &Addr::unchecked("cheater")));
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(env!("CARGO_PKG_VERSION"), version.version);
+
+        // migrating again on the same version leaves the data alone
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(1, GAME_COUNT.load(deps.as_ref().storage).unwrap());
+    }
+
+    #[test]
+    fn migrate_rejects_downgrade_and_other_contracts() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        set_contract_version(deps.as_mut().storage, "crates.io:rps-dapp", "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::CannotDowngrade { .. }));
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::WrongContract { .. }));
+    }
+}
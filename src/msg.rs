@@ -1,46 +1,371 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, Order, Uint128};
+use cw0::{Duration, Expiration};
+use cw20::{Cw20ReceiveMsg, Denom};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{Game, GameMove};
+use crate::state::{
+    Asset, BlacklistEntry, DailyActivity, Game, GameMove, GameStatus, HeadToHeadGame, PauseScope,
+    PlayerStats,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct InstantiateMsg {
+    pub response_timeout: Option<Duration>,
+    pub reveal_timeout: Option<Duration>,
+    pub fee_bps: Option<u16>,
+    pub default_rating: Option<u32>,
+    pub k_factor: Option<u32>,
+    pub invite_only: Option<bool>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct MigrateMsg {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// `host_commitment` is the sha256 of the host's move, a secret salt and the
+    /// host address concatenated (see `contract::hash_move`). Without an `opponent`
+    /// the game is an open challenge and the first address to respond plays it.
     StartGame {
-        opponent: Addr,
-        host_move: GameMove,
+        opponent: Option<Addr>,
+        host_commitment: Binary,
     },
     UpdateAdmin {
         admin: Addr,
     },
+    /// Bans an address from playing until `expires`, or for good without it. Games
+    /// the address is still playing are left to `CancelBannedGames`.
+    /// Adding an address already on the list replaces its entry.
     AddToBlacklist {
         address: Addr,
+        reason: Option<String>,
+        expires: Option<Expiration>,
     },
     RemoveFromBlacklist {
         address: Addr,
     },
+    /// Cancels up to `limit` (at most 30) games a blacklisted address is still
+    /// playing and refunds their stakes. The `games_remaining` attribute tells
+    /// whether another call is needed.
+    CancelBannedGames {
+        address: Addr,
+        limit: Option<u32>,
+    },
     Respond {
-        host: Addr,
+        game_id: u64,
+        opp_move: GameMove,
+    },
+    /// Sent by the host once the opponent has responded, to open the commitment.
+    /// For a match round that does not decide the match, `next_commitment` opens
+    /// the following round.
+    Reveal {
+        game_id: u64,
+        host_move: GameMove,
+        salt: String,
+        next_commitment: Option<Binary>,
+    },
+    /// Ends a game whose current deadline has passed. Before a response the host
+    /// cancels it; before a reveal the opponent wins by forfeit.
+    ClaimTimeout {
+        game_id: u64,
+    },
+    /// Withdraws a challenge nobody has responded to yet, refunding the host.
+    CancelGame {
+        game_id: u64,
+        reason: Option<String>,
+    },
+    /// Lets the named opponent turn down a challenge, refunding the host.
+    DeclineGame {
+        game_id: u64,
+        reason: Option<String>,
+    },
+    UpdateConfig {
+        response_timeout: Option<Duration>,
+        reveal_timeout: Option<Duration>,
+        fee_bps: Option<u16>,
+        default_rating: Option<u32>,
+        k_factor: Option<u32>,
+    },
+    /// Starts a best-of-`best_of` match; the first round uses `host_commitment`.
+    /// The opponent matches the stake when responding to the first round.
+    StartMatch {
         opponent: Addr,
+        best_of: u8,
+        host_commitment: Binary,
+    },
+    /// Turns invite-only mode on or off. While it is on, only addresses on the
+    /// player allowlist can start, be challenged to or respond to games. A match
+    /// opponent shut out after the match started does not forfeit on timeout, the match is cancelled and refunded.
+    SetInviteOnly {
+        enabled: bool,
+    },
+    AddToAllowlist {
+        address: Addr,
+    },
+    RemoveFromAllowlist {
+        address: Addr,
+    },
+    /// Halts play as far as `scope` reaches until `Unpause`. Pausing again
+    /// replaces the current scope and reason. No match forfeit can be claimed
+    /// during a full pause, nor for a response timeout after it is lifted.
+    Pause {
+        scope: PauseScope,
+        reason: Option<String>,
+    },
+    Unpause {},
+    /// Entry point for cw20 wagers, the embedded message is a `ReceiveMsg`.
+    Receive(Cw20ReceiveMsg),
+    AllowCw20 {
+        contract: Addr,
+    },
+    DisallowCw20 {
+        contract: Addr,
+    },
+    /// Sends accrued fees of one denom to `recipient` (defaults to the admin).
+    /// Without an `amount` the whole balance is withdrawn.
+    WithdrawFees {
+        denom: Denom,
+        amount: Option<Uint128>,
+        recipient: Option<Addr>,
+    },
+}
+
+/// Messages sent along with cw20 tokens; the received amount becomes the wager.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    StartGame {
+        opponent: Option<Addr>,
+        host_commitment: Binary,
+    },
+    Respond {
+        game_id: u64,
         opp_move: GameMove,
     },
+    StartMatch {
+        opponent: Addr,
+        best_of: u8,
+        host_commitment: Binary,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetGamesByOpponent { opponent: Addr },
-    GetGamesByHost { address: Addr },
+    /// Games still in play with `opponent` as the opponent. Closed games are
+    /// listed by `GetPlayerHistory` or `ListGames`.
+    GetGamesByOpponent {
+        opponent: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Games still in play hosted by `address`. Closed games are listed by
+    /// `GetPlayerHistory` or `ListGames`.
+    GetGamesByHost {
+        address: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Games by status, optionally only those `player` takes part in.
+    ListGames {
+        status: Option<GameStatus>,
+        player: Option<Addr>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
     GetOwner {},
-    GetGame { host: Addr, opponent: Addr },
+    GetGame {
+        game_id: u64,
+    },
+    /// Finished, cancelled and expired games the player took part in, as host or
+    /// opponent.
+    GetPlayerHistory {
+        player: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
     GetAdmin {},
+    GetConfig {},
+    /// The current pause, null while play is open.
+    GetPauseStatus {},
+    GetCw20Allowlist {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// Addresses on the player allowlist.
+    ListAllowlist {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// Whether an address is on the player allowlist, whatever the current mode.
+    IsAllowlisted {
+        address: Addr,
+    },
+    /// Blacklist entries by address, including expired ones.
+    ListBlacklist {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// Fee balances, paged by denom key: the native denom or the cw20 contract address.
+    GetAccruedFees {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Challenges still waiting for an opponent, optionally only those at `stake`.
+    GetOpenChallenges {
+        stake: Option<Asset>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    GetMatch {
+        match_id: u64,
+    },
+    GetPlayerStats {
+        address: Addr,
+    },
+    GetGlobalStats {},
+    /// Daily activity between two days, inclusive, counted in days since the unix
+    /// epoch (block time in seconds / 86400). Days without games are left out.
+    GetActivity {
+        from: u64,
+        to: u64,
+    },
+    /// Move counts of a single player, or of the whole contract without an `address`.
+    GetMoveStats {
+        address: Option<Addr>,
+    },
+    /// Elo rating of a player, the configured default if they have not played yet.
+    GetRating {
+        address: Addr,
+    },
+    /// Stats of up to 30 addresses at once, in the order they were given.
+    GetPlayerStatsBatch {
+        addresses: Vec<Addr>,
+    },
+    /// Record between two players, from the point of view of `a`.
+    GetHeadToHead {
+        a: Addr,
+        b: Addr,
+    },
+    /// Players ranked by `metric`, best first. Page with the last entry returned,
+    /// its score keeps the cursor in place when the player's score changes since.
+    GetLeaderboard {
+        metric: LeaderboardMetric,
+        start_after: Option<LeaderboardEntry>,
+        limit: Option<u32>,
+    },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardMetric {
+    Wins,
+    /// Wins per game played in basis points, for players with at least 10 games.
+    WinRate,
+    /// Payouts minus wagers in a single denom.
+    NetWinnings {
+        denom: Denom,
+    },
+    Rating,
+}
+
+/// Direction list queries walk their keys in, ascending by default.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Ascending,
+    Descending,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Order {
+        match order {
+            OrderBy::Ascending => Order::Ascending,
+            OrderBy::Descending => Order::Descending,
+        }
+    }
+}
+
+/// `next_start_after` is set when the page is full; pass it as `start_after` to
+/// fetch the next one.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GamesListResponse {
     pub games: Vec<Game>,
+    pub next_start_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerStatsEntry {
+    pub address: Addr,
+    pub stats: PlayerStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerStatsBatchResponse {
+    pub stats: Vec<PlayerStatsEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HeadToHeadResponse {
+    pub a_wins: u64,
+    pub b_wins: u64,
+    pub ties: u64,
+    /// Latest games between the two, most recent first.
+    pub recent: Vec<HeadToHeadGame>,
+    pub streak_holder: Option<Addr>,
+    pub streak: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ActivityResponse {
+    pub days: Vec<DailyActivity>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardEntry {
+    pub address: Addr,
+    pub score: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntry>,
+    pub next_start_after: Option<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20AllowlistResponse {
+    pub contracts: Vec<Addr>,
+    pub next_start_after: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowlistResponse {
+    pub addresses: Vec<Addr>,
+    pub next_start_after: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlacklistEntryResponse {
+    pub address: Addr,
+    pub entry: BlacklistEntry,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlacklistResponse {
+    pub entries: Vec<BlacklistEntryResponse>,
+    pub next_start_after: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeesResponse {
+    pub fees: Vec<Asset>,
+    pub next_start_after: Option<String>,
 }
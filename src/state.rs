@@ -1,30 +1,434 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cw_controllers::{Admin, Hooks};
+use cw_controllers::Admin;
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use std::convert::TryFrom;
+use std::fmt;
+
+use cosmwasm_std::{Addr, Binary, Empty, Timestamp, Uint128};
+use cw0::{Duration, Expiration};
+use cw20::Denom;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlacklistEntry {
+    pub reason: Option<String>,
+    pub added_by: Addr,
+    pub added_at: Timestamp,
+    pub expires: Option<Expiration>,
+}
+
+/// What a pause blocks. Revealing, cancelling and claiming timeouts stay open
+/// either way so escrowed stakes can always be released.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseScope {
+    /// No games or matches can be started, games already started play on.
+    NewGames,
+    /// Starting games and responding to them are both blocked.
+    All,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseInfo {
+    pub scope: PauseScope,
+    pub reason: Option<String>,
+    pub paused_by: Addr,
+    pub paused_at: Timestamp,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub owner: Addr,
+    /// How long an opponent has to respond before the host can cancel the game.
+    pub response_timeout: Duration,
+    /// How long the host has to reveal before the opponent can claim the win.
+    pub reveal_timeout: Duration,
+    /// House fee in basis points, taken from every stake of a resolved game.
+    pub fee_bps: u16,
+    /// Elo rating players start from before their first resolved game.
+    pub default_rating: u32,
+    /// Largest rating change a single game can cause.
+    pub k_factor: u32,
+    /// Only addresses on `PLAYER_ALLOWLIST` may start or respond to games.
+    pub invite_only: bool,
 }
 
 pub const STATE: Item<State> = Item::new("state");
-pub const GAME: Map<(&Addr, &Addr), Game> = Map::new("game");
+/// Id of the last game started, ids begin at 1.
+pub const GAME_COUNT: Item<u64> = Item::new("game_count");
+/// Id of the last match started, ids begin at 1.
+pub const MATCH_COUNT: Item<u64> = Item::new("match_count");
+pub const MATCHES: Map<u64, Match> = Map::new("matches");
 pub const ADMIN: Admin = Admin::new("admin");
-pub const HOOKS: Hooks = Hooks::new("cw4-hooks");
+/// Banned addresses; entries past their `expires` no longer apply.
+pub const BLACKLIST: Map<&Addr, BlacklistEntry> = Map::new("blacklist");
+pub const CW20_ALLOWLIST: Map<&Addr, Empty> = Map::new("cw20_allowlist");
+/// Set while play is paused, removed again on unpause.
+pub const PAUSE: Item<PauseInfo> = Item::new("pause");
+/// Once a full pause is lifted, unanswered rounds cannot be claimed as a forfeit
+/// before this, so opponents get a whole response window back.
+pub const RESPONSE_GRACE: Item<Expiration> = Item::new("response_grace");
+/// Players allowed to take part while the contract is invite-only.
+pub const PLAYER_ALLOWLIST: Map<&Addr, Empty> = Map::new("player_allowlist");
+/// Accrued house fees, keyed by `denom_key`.
+pub const FEES: Map<&str, Asset> = Map::new("fees");
+pub const PLAYER_STATS: Map<&Addr, PlayerStats> = Map::new("player_stats");
+pub const GLOBAL_STATS: Item<GlobalStats> = Item::new("global_stats");
+pub const MOVE_STATS: Item<MoveStats> = Item::new("move_stats");
+/// Activity per day, keyed by days since the unix epoch of the block time.
+pub const DAILY_ACTIVITY: Map<u64, DailyActivity> = Map::new("daily_activity");
+/// Players seen on a day, so each one is counted once in `DailyActivity`.
+pub const DAILY_PLAYERS: Map<(u64, &Addr), Empty> = Map::new("daily_players");
+pub const PLAYER_MOVE_STATS: Map<&Addr, MoveStats> = Map::new("player_move_stats");
+/// Leaderboard rankings keyed by (score, player), read back in descending order.
+/// Entries are moved whenever the player's stats change.
+pub const RANK_WINS: Map<(i64, &Addr), Empty> = Map::new("rank_wins");
+pub const RANK_WIN_RATE: Map<(i64, &Addr), Empty> = Map::new("rank_win_rate");
+pub const RANK_RATING: Map<(i64, &Addr), Empty> = Map::new("rank_rating");
+/// Record between two players, keyed by the pair sorted by address.
+pub const HEAD_TO_HEAD: Map<(&Addr, &Addr), HeadToHead> = Map::new("head_to_head");
+/// Net winnings rankings, one per denom, keyed by (`denom_key`, score, player).
+pub const RANK_NET_WINNINGS: Map<(&str, i64, &Addr), Empty> = Map::new("rank_net_winnings");
+
+pub struct GameIndexes<'a> {
+    pub host: MultiIndex<'a, Addr, Game, u64>,
+    pub opponent: MultiIndex<'a, String, Game, u64>,
+    pub status: MultiIndex<'a, String, Game, u64>,
+    pub host_status: MultiIndex<'a, (Addr, String), Game, u64>,
+    /// (opponent, status), open challenges sit under the empty opponent.
+    pub opponent_status: MultiIndex<'a, (String, String), Game, u64>,
+    /// (opponent, status, `stake_key`), to find open challenges at a given stake.
+    pub opponent_status_stake: MultiIndex<'a, (String, String, String), Game, u64>,
+}
+
+impl<'a> IndexList<Game> for GameIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Game>> + '_> {
+        let v: Vec<&dyn Index<Game>> = vec![
+            &self.host,
+            &self.opponent,
+            &self.status,
+            &self.host_status,
+            &self.opponent_status,
+            &self.opponent_status_stake,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Games keyed by id, indexed by host, by opponent and by status. Open challenges
+/// are indexed under an empty opponent until someone accepts them.
+pub fn games<'a>() -> IndexedMap<'a, u64, Game, GameIndexes<'a>> {
+    let indexes = GameIndexes {
+        host: MultiIndex::new(|g: &Game| g.host.clone(), "games", "games__host"),
+        opponent: MultiIndex::new(
+            |g: &Game| {
+                g.opponent
+                    .as_ref()
+                    .map_or_else(String::new, Addr::to_string)
+            },
+            "games",
+            "games__opponent",
+        ),
+        status: MultiIndex::new(
+            |g: &Game| g.status.as_str().to_string(),
+            "games",
+            "games__status",
+        ),
+        host_status: MultiIndex::new(
+            |g: &Game| (g.host.clone(), g.status.as_str().to_string()),
+            "games",
+            "games__host_status",
+        ),
+        opponent_status: MultiIndex::new(
+            |g: &Game| {
+                let opponent = g
+                    .opponent
+                    .as_ref()
+                    .map_or_else(String::new, Addr::to_string);
+                (opponent, g.status.as_str().to_string())
+            },
+            "games",
+            "games__opponent_status",
+        ),
+        opponent_status_stake: MultiIndex::new(
+            |g: &Game| {
+                let opponent = g
+                    .opponent
+                    .as_ref()
+                    .map_or_else(String::new, Addr::to_string);
+                (
+                    opponent,
+                    g.status.as_str().to_string(),
+                    stake_key(g.stake.as_ref()),
+                )
+            },
+            "games",
+            "games__opponent_status_stake",
+        ),
+    };
+    IndexedMap::new("games", indexes)
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Game {
+    pub id: u64,
     pub host: Addr,
-    pub opponent: Addr,
-    pub host_move: GameMove,
+    /// `None` for an open challenge that any address can accept.
+    pub opponent: Option<Addr>,
+    pub host_commitment: Binary,
+    pub host_move: Option<GameMove>,
     pub opp_move: Option<GameMove>,
+    pub status: GameStatus,
+    pub result: Option<GameResult>,
+    /// Wager escrowed by each player; the opponent has to match the host's stake.
+    pub stake: Option<Asset>,
+    pub created_height: u64,
+    pub created_time: Timestamp,
+    /// Deadline of the current phase, after which `ClaimTimeout` is allowed.
+    pub expires: Expiration,
+    /// Set when the game is a round of a best-of-N match.
+    pub match_id: Option<u64>,
+    /// Block at which the game left play, whether finished, cancelled or expired.
+    pub finished_height: Option<u64>,
+    pub finished_time: Option<Timestamp>,
+}
+
+/// A best-of-N series between two players, played as a sequence of games. The
+/// stake is escrowed on the match and settled once a player wins the majority.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Match {
+    pub id: u64,
+    pub host: Addr,
+    pub opponent: Addr,
+    pub best_of: u8,
+    pub host_score: u8,
+    pub opp_score: u8,
+    pub rounds: Vec<Round>,
+    /// Game currently being played, `None` once the match is over.
+    pub current_game: Option<u64>,
+    pub stake: Option<Asset>,
+    pub status: MatchStatus,
     pub result: Option<GameResult>,
 }
 
+impl Match {
+    /// Rounds a player has to win to take the match.
+    pub fn wins_needed(&self) -> u8 {
+        self.best_of / 2 + 1
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Round {
+    pub game_id: u64,
+    pub host_move: GameMove,
+    pub opp_move: GameMove,
+    pub result: GameResult,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStatus {
+    InProgress,
+    Finished,
+    /// Called off without a result: withdrawn, declined or timed out before the
+    /// first response, or stopped when a player was barred. Stakes were refunded.
+    Cancelled,
+}
+
+/// Contract-wide counters, kept up to date as games move through their lifecycle.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct GlobalStats {
+    pub games_started: u64,
+    pub games_resolved: u64,
+    pub games_cancelled: u64,
+    pub games_expired: u64,
+    pub ties: u64,
+    /// Addresses with at least one resolved game.
+    pub distinct_players: u64,
+    /// Stakes of both players in resolved games and matches, one entry per denom.
+    pub volume: Vec<Asset>,
+    /// House fees taken over the contract's lifetime, withdrawals not deducted.
+    pub fees_collected: Vec<Asset>,
+}
+
+/// Games resolved on one day, by block time, and who played them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct DailyActivity {
+    /// Days since the unix epoch.
+    pub day: u64,
+    pub games_played: u64,
+    pub unique_players: u64,
+    /// Stakes of both players in games and matches settled that day, per denom.
+    pub volume: Vec<Asset>,
+}
+
+/// How often each move was played in finished games, and how often it won.
+/// A host move only counts once it has been revealed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MoveStats {
+    pub as_host: MoveCounts,
+    pub as_opponent: MoveCounts,
+    pub wins: MoveCounts,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MoveCounts {
+    pub rock: u64,
+    pub paper: u64,
+    pub scissors: u64,
+}
+
+impl MoveCounts {
+    pub fn add(&mut self, game_move: GameMove) {
+        match game_move {
+            GameMove::Rock => self.rock += 1,
+            GameMove::Paper => self.paper += 1,
+            GameMove::Scissors => self.scissors += 1,
+        }
+    }
+}
+
+/// Record of a player across every resolved game, match rounds included.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PlayerStats {
+    pub wins: u64,
+    pub losses: u64,
+    pub ties: u64,
+    pub games_played: u64,
+    /// Stakes put into resolved games and matches, one entry per denom.
+    pub wagered: Vec<Asset>,
+    /// Stakes paid back to the player after the house fee, ties included.
+    pub won: Vec<Asset>,
+    pub last_played_height: u64,
+    /// Elo rating, `None` until the player's first resolved game.
+    pub rating: Option<u32>,
+}
+
+impl PlayerStats {
+    /// Share of games won in basis points, once at least `min_games` were played.
+    pub fn win_rate_bps(&self, min_games: u64) -> Option<i64> {
+        if self.games_played == 0 || self.games_played < min_games {
+            return None;
+        }
+        Some((self.wins * 10_000 / self.games_played) as i64)
+    }
+
+    /// Payouts minus wagers in `denom`, saturating at the bounds of an i64.
+    pub fn net_winnings(&self, denom: &Denom) -> i64 {
+        let total = |assets: &[Asset]| {
+            assets
+                .iter()
+                .find(|asset| &asset.denom == denom)
+                .map_or(0, |asset| asset.amount.u128())
+        };
+        let (won, wagered) = (total(&self.won), total(&self.wagered));
+
+        if won >= wagered {
+            i64::try_from(won - wagered).unwrap_or(i64::MAX)
+        } else {
+            i64::try_from(wagered - won).map_or(i64::MIN, |loss| -loss)
+        }
+    }
+}
+
+/// Results between the two players of a `HEAD_TO_HEAD` key; `first` is the lower
+/// of the two addresses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct HeadToHead {
+    pub first_wins: u64,
+    pub second_wins: u64,
+    pub ties: u64,
+    /// Latest games between the pair, most recent first.
+    pub recent: Vec<HeadToHeadGame>,
+    /// Player on a winning run, if the last game was not a tie.
+    pub streak_holder: Option<Addr>,
+    pub streak: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HeadToHeadGame {
+    pub game_id: u64,
+    /// `None` for a tie.
+    pub winner: Option<Addr>,
+}
+
+/// An amount of either a native coin or a token of an allowlisted cw20 contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Asset {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.denom {
+            Denom::Native(denom) => write!(f, "{}{}", self.amount, denom),
+            Denom::Cw20(contract) => write!(f, "{} {}", self.amount, contract),
+        }
+    }
+}
+
+/// Storage key for per-denom balances: the native denom or the cw20 contract address.
+pub fn denom_key(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => denom.clone(),
+        Denom::Cw20(contract) => contract.to_string(),
+    }
+}
+
+/// Index key for a wager, `denom_key` and amount; empty for a game without stake.
+pub fn stake_key(stake: Option<&Asset>) -> String {
+    match stake {
+        Some(stake) => format!("{}:{}", denom_key(&stake.denom), stake.amount),
+        None => String::new(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStatus {
+    AwaitingResponse,
+    AwaitingReveal,
+    /// Resolved games are kept as history with both moves and the result.
+    Finished,
+    /// Withdrawn by the host or declined by the opponent before a response.
+    Cancelled,
+    /// Nobody responded before the deadline and the host claimed the timeout.
+    Expired,
+}
+
+impl GameStatus {
+    /// Statuses of games that can still be played.
+    pub const ACTIVE: [GameStatus; 2] = [GameStatus::AwaitingResponse, GameStatus::AwaitingReveal];
+    /// Statuses of games kept as history.
+    pub const CLOSED: [GameStatus; 3] = [
+        GameStatus::Finished,
+        GameStatus::Cancelled,
+        GameStatus::Expired,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameStatus::AwaitingResponse => "awaiting_response",
+            GameStatus::AwaitingReveal => "awaiting_reveal",
+            GameStatus::Finished => "finished",
+            GameStatus::Cancelled => "cancelled",
+            GameStatus::Expired => "expired",
+        }
+    }
+
+    /// Whether the game can still be played.
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            GameStatus::AwaitingResponse | GameStatus::AwaitingReveal
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
 pub enum GameMove {
     Rock,
@@ -32,6 +436,16 @@ pub enum GameMove {
     Scissors,
 }
 
+impl GameMove {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameMove::Rock => "rock",
+            GameMove::Paper => "paper",
+            GameMove::Scissors => "scissors",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum GameResult {
     HostWins,